@@ -1,9 +1,9 @@
-use std::{collections::HashMap, fs};
+use std::collections::HashMap;
 
 fn main() {
     println!("Hello, world!");
 
-    let content = fs::read_to_string("./input.txt").unwrap();
+    let content = input::read("./input.txt");
 
     let lines: Vec<_> = content.split('\n').collect();
     let res = calibrate(lines);
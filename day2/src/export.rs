@@ -0,0 +1,125 @@
+use serde::Serialize;
+
+use crate::{Cube, CubeSet, Game};
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct Row<'a> {
+    pub game: u32,
+    pub draw: usize,
+    pub color: &'a str,
+    pub count: u32,
+}
+
+pub fn rows(games: &[Game]) -> Vec<Row<'_>> {
+    let mut rows = vec![];
+
+    for Game(id, cubesets) in games {
+        for (draw, CubeSet(cubes)) in cubesets.iter().enumerate() {
+            for Cube(count, color) in cubes {
+                rows.push(Row {
+                    game: *id,
+                    draw,
+                    color,
+                    count: *count,
+                });
+            }
+        }
+    }
+
+    rows
+}
+
+pub fn to_csv(games: &[Game]) -> String {
+    let mut out = String::from("game,draw,color,count\n");
+
+    for Row {
+        game,
+        draw,
+        color,
+        count,
+    } in rows(games)
+    {
+        out.push_str(&format!("{game},{draw},{color},{count}\n"));
+    }
+
+    out
+}
+
+pub fn to_json(games: &[Game]) -> String {
+    serde_json::to_string_pretty(&rows(games)).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn games() -> Vec<Game> {
+        vec![
+            Game(
+                1,
+                vec![
+                    CubeSet(vec![
+                        Cube(3, "blue".to_string()),
+                        Cube(4, "red".to_string()),
+                    ]),
+                    CubeSet(vec![Cube(2, "green".to_string())]),
+                ],
+            ),
+            Game(2, vec![CubeSet(vec![Cube(1, "red".to_string())])]),
+        ]
+    }
+
+    #[test]
+    pub fn test_rows() {
+        let games = games();
+
+        assert_eq!(
+            rows(&games),
+            vec![
+                Row {
+                    game: 1,
+                    draw: 0,
+                    color: "blue",
+                    count: 3
+                },
+                Row {
+                    game: 1,
+                    draw: 0,
+                    color: "red",
+                    count: 4
+                },
+                Row {
+                    game: 1,
+                    draw: 1,
+                    color: "green",
+                    count: 2
+                },
+                Row {
+                    game: 2,
+                    draw: 0,
+                    color: "red",
+                    count: 1
+                },
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_to_csv() {
+        assert_eq!(
+            to_csv(&games()),
+            "game,draw,color,count\n1,0,blue,3\n1,0,red,4\n1,1,green,2\n2,0,red,1\n"
+        );
+    }
+
+    #[test]
+    pub fn test_to_json() {
+        let json: serde_json::Value = serde_json::from_str(&to_json(&games())).unwrap();
+
+        assert_eq!(json.as_array().unwrap().len(), 4);
+        assert_eq!(
+            json[2],
+            serde_json::json!({ "game": 1, "draw": 1, "color": "green", "count": 2 })
+        );
+    }
+}
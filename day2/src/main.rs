@@ -1,8 +1,11 @@
-use std::{collections::HashMap, env::args, fs};
+use std::{collections::HashMap, env::args};
 
 use chumsky::prelude::*;
 use std::iter::Iterator;
 
+mod export;
+mod summary;
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct Cube(u32, String);
 #[derive(Debug, PartialEq, Eq)]
@@ -56,6 +59,10 @@ pub fn minimum_cubes(Game(.., cubesets): &Game, color: &str) -> u32 {
     min
 }
 
+pub fn power(game: &Game) -> u32 {
+    minimum_cubes(game, "red") * minimum_cubes(game, "blue") * minimum_cubes(game, "green")
+}
+
 pub fn is_game_possible(Game(.., cubesets): &Game) -> bool {
     let mut hashmap: HashMap<&str, u32> = HashMap::new();
     hashmap.insert("red", 12);
@@ -78,30 +85,41 @@ fn main() {
     let mut args = args();
     args.next();
 
-    let content = fs::read_to_string(args.next().expect("file name")).expect("file content");
+    let content = input::read(args.next().expect("file name"));
 
+    let games = content
+        .split('\n')
+        .filter(|line| !line.is_empty())
+        .map(|line| game_parser().parse(line).unwrap())
+        .collect::<Vec<_>>();
+
+    match args.next().as_deref() {
+        Some("csv") => print!("{}", export::to_csv(&games)),
+        Some("json") => println!("{}", export::to_json(&games)),
+        Some("summary") => print!("{}", summary::Summary::new(&games)),
+        Some(mode) => panic!("Unknown mode {mode}, expected csv, json or summary"),
+        None => report(&games),
+    }
+}
+
+fn report(games: &[Game]) {
     let mut possible_games = 0;
     let mut power_sum = 0;
-    for line in content.split('\n') {
-        if line.is_empty() {
-            continue;
-        }
-
-        let game = game_parser().parse(line).unwrap();
-        if is_game_possible(&game) {
+    for game in games {
+        if is_game_possible(game) {
             possible_games += game.0;
         }
 
-        let min_red = minimum_cubes(&game, "red");
-        let min_blue = minimum_cubes(&game, "blue");
-        let min_green = minimum_cubes(&game, "green");
-        let power = min_red * min_blue * min_green;
+        let min_red = minimum_cubes(game, "red");
+        let min_blue = minimum_cubes(game, "blue");
+        let min_green = minimum_cubes(game, "green");
+        let power = power(game);
 
         println!("Game {}", game.0);
         println!("  Red: {min_red}, Blue: {min_blue}, Green: {min_green}");
         println!("  Power: {power}");
 
-        power_sum += min_red * min_blue * min_green;
+        power_sum += power;
     }
 
     println!("Possible games: {}", possible_games);
@@ -112,6 +130,7 @@ fn main() {
 mod tests {
     use super::*;
 
+    #[test]
     pub fn test_minimum_cubes() {
         let game = Game(
             1,
@@ -129,8 +148,8 @@ mod tests {
             ],
         );
 
-        assert_eq!(minimum_cubes(&game, "blue"), 3);
-        assert_eq!(minimum_cubes(&game, "red"), 1);
+        assert_eq!(minimum_cubes(&game, "blue"), 6);
+        assert_eq!(minimum_cubes(&game, "red"), 4);
         assert_eq!(minimum_cubes(&game, "green"), 2);
     }
 
@@ -0,0 +1,106 @@
+use std::{collections::BTreeMap, fmt};
+
+use crate::{power, Cube, CubeSet, Game};
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Summary {
+    /// For each color, how many draws showed a given number of cubes.
+    pub histograms: BTreeMap<String, BTreeMap<u32, usize>>,
+    pub maxima: BTreeMap<String, u32>,
+    /// How many games have a given power.
+    pub powers: BTreeMap<u32, usize>,
+}
+
+impl Summary {
+    pub fn new(games: &[Game]) -> Self {
+        let mut summary = Summary::default();
+
+        for game in games {
+            let Game(.., cubesets) = game;
+
+            for CubeSet(cubes) in cubesets {
+                for Cube(count, color) in cubes {
+                    *summary
+                        .histograms
+                        .entry(color.clone())
+                        .or_default()
+                        .entry(*count)
+                        .or_default() += 1;
+
+                    let max = summary.maxima.entry(color.clone()).or_default();
+                    if *count > *max {
+                        *max = *count;
+                    }
+                }
+            }
+
+            *summary.powers.entry(power(game)).or_default() += 1;
+        }
+
+        summary
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (color, histogram) in &self.histograms {
+            writeln!(f, "{color} (max {})", self.maxima[color])?;
+            for (count, draws) in histogram {
+                writeln!(f, "  {count:>3} | {}", "#".repeat(*draws))?;
+            }
+        }
+
+        writeln!(f, "powers")?;
+        for (power, games) in &self.powers {
+            writeln!(f, "  {power:>5} | {}", "#".repeat(*games))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_summary() {
+        let games = vec![
+            Game(
+                1,
+                vec![
+                    CubeSet(vec![
+                        Cube(3, "blue".to_string()),
+                        Cube(4, "red".to_string()),
+                    ]),
+                    CubeSet(vec![
+                        Cube(1, "red".to_string()),
+                        Cube(2, "green".to_string()),
+                        Cube(6, "blue".to_string()),
+                    ]),
+                    CubeSet(vec![Cube(2, "green".to_string())]),
+                ],
+            ),
+            Game(
+                2,
+                vec![CubeSet(vec![
+                    Cube(1, "blue".to_string()),
+                    Cube(2, "green".to_string()),
+                    Cube(4, "red".to_string()),
+                ])],
+            ),
+        ];
+
+        let summary = Summary::new(&games);
+
+        assert_eq!(
+            summary.histograms["green"],
+            BTreeMap::from([(2, 3)]),
+            "green was drawn three times with 2 cubes"
+        );
+        assert_eq!(summary.histograms["red"], BTreeMap::from([(1, 1), (4, 2)]));
+        assert_eq!(summary.maxima["blue"], 6);
+        assert_eq!(summary.maxima["red"], 4);
+        assert_eq!(summary.powers, BTreeMap::from([(8, 1), (48, 1)]));
+    }
+}
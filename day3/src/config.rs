@@ -0,0 +1,170 @@
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CharClass {
+    /// Any ASCII punctuation except `.`
+    Punctuation,
+    Only(Vec<char>),
+}
+
+impl CharClass {
+    pub fn contains(&self, c: char) -> bool {
+        match self {
+            CharClass::Punctuation => c != '.' && c.is_ascii_punctuation(),
+            CharClass::Only(chars) => chars.contains(&c),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arity {
+    Exactly(usize),
+    AtLeast(usize),
+}
+
+impl Arity {
+    pub fn accepts(&self, count: usize) -> bool {
+        match self {
+            Arity::Exactly(n) => count == *n,
+            Arity::AtLeast(n) => count >= *n,
+        }
+    }
+}
+
+impl FromStr for Arity {
+    type Err = String;
+
+    /// `2` means exactly two numbers, `2+` at least two.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (n, at_least) = match s.strip_suffix('+') {
+            Some(n) => (n, true),
+            None => (s, false),
+        };
+
+        let n = n.parse::<usize>().map_err(|e| format!("{s}: {e}"))?;
+
+        Ok(if at_least {
+            Arity::AtLeast(n)
+        } else {
+            Arity::Exactly(n)
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Combine {
+    Product,
+    Sum,
+}
+
+impl Combine {
+    pub fn apply(&self, values: impl Iterator<Item = usize>) -> usize {
+        match self {
+            Combine::Product => values.product(),
+            Combine::Sum => values.sum(),
+        }
+    }
+}
+
+impl FromStr for Combine {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "product" => Ok(Combine::Product),
+            "sum" => Ok(Combine::Sum),
+            s => Err(format!("{s}: expected product or sum")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub symbols: CharClass,
+    /// Gears also count as symbols for part 1.
+    pub gears: CharClass,
+    pub arity: Arity,
+    pub combine: Combine,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            symbols: CharClass::Punctuation,
+            gears: CharClass::Only(vec!['*']),
+            arity: Arity::Exactly(2),
+            combine: Combine::Product,
+        }
+    }
+}
+
+impl Config {
+    pub fn is_symbol(&self, c: char) -> bool {
+        self.symbols.contains(c) || self.is_gear(c)
+    }
+
+    pub fn is_gear(&self, c: char) -> bool {
+        self.gears.contains(c)
+    }
+
+    /// Reads `--symbols`, `--gears`, `--arity` and `--combine` options.
+    pub fn from_args(args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut config = Config::default();
+        let mut args = args;
+
+        while let Some(arg) = args.next() {
+            let value = args.next().ok_or(format!("missing value for {arg}"))?;
+
+            match arg.as_str() {
+                "--symbols" => config.symbols = CharClass::Only(value.chars().collect()),
+                "--gears" => config.gears = CharClass::Only(value.chars().collect()),
+                "--arity" => config.arity = value.parse()?,
+                "--combine" => config.combine = value.parse()?,
+                arg => return Err(format!("unknown option {arg}")),
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_char_class() {
+        assert!(CharClass::Punctuation.contains('#'));
+        assert!(!CharClass::Punctuation.contains('.'));
+        assert!(!CharClass::Punctuation.contains('a'));
+        assert!(CharClass::Only(vec!['a']).contains('a'));
+    }
+
+    #[test]
+    fn test_arity() {
+        assert_eq!("2".parse(), Ok(Arity::Exactly(2)));
+        assert_eq!("3+".parse(), Ok(Arity::AtLeast(3)));
+        assert!("x".parse::<Arity>().is_err());
+
+        assert!(Arity::AtLeast(2).accepts(3));
+        assert!(!Arity::Exactly(2).accepts(3));
+    }
+
+    #[test]
+    fn test_from_args() {
+        let args = ["--gears", "*#", "--arity", "1+", "--combine", "sum"];
+        let config = Config::from_args(args.iter().map(|s| s.to_string())).unwrap();
+
+        assert_eq!(
+            config,
+            Config {
+                symbols: CharClass::Punctuation,
+                gears: CharClass::Only(vec!['*', '#']),
+                arity: Arity::AtLeast(1),
+                combine: Combine::Sum,
+            }
+        );
+
+        assert!(Config::from_args(["--arity".to_string()].into_iter()).is_err());
+    }
+}
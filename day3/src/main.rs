@@ -1,14 +1,42 @@
-use std::{env::args, fs, ops::RangeBounds};
+use std::env::args;
+
+use config::Config;
+use grid::Grid;
+
+mod config;
+mod grid;
+mod render;
 
 #[derive(Debug, Clone)]
 enum TokenKind {
-    Number(usize),
+    /// `id` is unique per number, in reading order.
+    Number {
+        id: usize,
+        value: usize,
+    },
     Digit(char),
     Dot,
-    Gear,
+    Symbol(char),
     Unknown,
 }
 
+impl TokenKind {
+    fn is_symbol(&self, config: &Config) -> bool {
+        matches!(self, TokenKind::Symbol(c) if config.is_symbol(*c))
+    }
+
+    fn is_gear(&self, config: &Config) -> bool {
+        matches!(self, TokenKind::Symbol(c) if config.is_gear(*c))
+    }
+
+    fn value(&self) -> Option<usize> {
+        match self {
+            TokenKind::Number { value, .. } => Some(*value),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 struct Token {
     line: usize,
@@ -26,13 +54,26 @@ fn parse(src: &str) -> Vec<Token> {
     let mut digits = vec![];
     let mut start = None;
     let mut pos = 0;
+    let mut id = 0;
+
+    let mut number = |line, start, end, digits: &[char]| {
+        let value = String::from_iter(digits).parse::<usize>().unwrap();
+        id += 1;
+
+        Token {
+            line,
+            start,
+            end,
+            kind: TokenKind::Number { id: id - 1, value },
+        }
+    };
 
     for char in src.chars() {
         let kind = match char {
             '.' => TokenKind::Dot,
-            '*' => TokenKind::Gear,
             c if c.is_ascii_digit() => TokenKind::Digit(c),
-            _ => TokenKind::Unknown,
+            c if c.is_whitespace() => TokenKind::Unknown,
+            c => TokenKind::Symbol(c),
         };
 
         if let TokenKind::Digit(c) = kind {
@@ -42,13 +83,7 @@ fn parse(src: &str) -> Vec<Token> {
 
             digits.push(c);
         } else if !digits.is_empty() {
-            let num = String::from_iter(&digits).parse::<usize>().unwrap();
-            tokens.push(Token {
-                line,
-                start: start.unwrap(),
-                end: pos - 1,
-                kind: TokenKind::Number(num),
-            });
+            tokens.push(number(line, start.unwrap(), pos - 1, &digits));
             digits = vec![];
         }
 
@@ -70,63 +105,231 @@ fn parse(src: &str) -> Vec<Token> {
         }
     }
 
+    // A number can end the input when there is no trailing newline
+    if !digits.is_empty() {
+        tokens.push(number(line, start.unwrap(), pos - 1, &digits));
+    }
+
     tokens
 }
 
-fn calculate_gear(tokens: &[Token], gear: &Token) -> Option<usize> {
-    let mut found = vec![];
+fn dimensions(tokens: &[Token]) -> (usize, usize) {
+    let width = tokens.iter().map(|tok| tok.end + 1).max().unwrap_or(0);
+    let height = tokens.iter().map(|tok| tok.line + 1).max().unwrap_or(0);
 
-    for line in gear.line - 1..gear.line + 2 {
-        for pos in gear.start - 1..gear.start + 2 {
-            let res = tokens
-                .iter()
-                .filter(|tok| matches!(tok.kind, TokenKind::Number(_)))
-                .find(|tok| tok.line == line && (tok.start..tok.end + 1).contains(&pos));
+    (width, height)
+}
 
-            if let Some(res) = res {
-                found.push(res);
-            }
+/// Maps every cell covered by a number to the token of that number.
+fn number_index(tokens: &[Token]) -> Grid<Option<&Token>> {
+    let (width, height) = dimensions(tokens);
+    let mut index = Grid::new(width, height, None);
+
+    for tok in tokens {
+        if !matches!(tok.kind, TokenKind::Number { .. }) {
+            continue;
         }
-    }
 
-    found.dedup_by(|a, b| {
-        if let (TokenKind::Number(num_a), TokenKind::Number(num_b)) = (&a.kind, &b.kind) {
-            return num_a == num_b && a.start == b.start && a.end == b.end && a.line == b.line;
+        for pos in tok.start..tok.end + 1 {
+            *index.get_mut(pos, tok.line).unwrap() = Some(tok);
         }
+    }
 
-        false
-    });
+    index
+}
 
-    if found.len() != 2 {
-        return None;
+fn symbol_index(tokens: &[Token], config: &Config) -> Grid<bool> {
+    let (width, height) = dimensions(tokens);
+    let mut index = Grid::new(width, height, false);
+
+    for tok in tokens.iter().filter(|tok| tok.kind.is_symbol(config)) {
+        *index.get_mut(tok.start, tok.line).unwrap() = true;
     }
 
-    let toks = found
-        .iter()
-        .map(|t| match t.kind {
-            TokenKind::Number(num) => num,
-            _ => 0,
+    index
+}
+
+fn is_part_number(symbols: &Grid<bool>, number: &Token) -> bool {
+    (number.start..number.end + 1).any(|pos| {
+        symbols
+            .neighbors8(pos, number.line)
+            .any(|(pos, line)| *symbols.get(pos, line).unwrap())
+    })
+}
+
+/// A number touching the gear through several cells is only counted once.
+fn calculate_gear(index: &Grid<Option<&Token>>, gear: &Token, config: &Config) -> Option<usize> {
+    let mut found = index
+        .neighbors8(gear.start, gear.line)
+        .filter_map(|(pos, line)| *index.get(pos, line).unwrap())
+        .filter_map(|tok| match tok.kind {
+            TokenKind::Number { id, value } => Some((id, value)),
+            _ => None,
         })
         .collect::<Vec<_>>();
 
-    Some(toks.first().unwrap() * toks.last().unwrap())
+    found.sort();
+    found.dedup();
+
+    if !config.arity.accepts(found.len()) {
+        return None;
+    }
+
+    Some(config.combine.apply(found.iter().map(|(_, value)| *value)))
 }
 
-fn calculate(tokens: Vec<Token>) -> usize {
+fn calculate1(tokens: &[Token], config: &Config) -> usize {
+    let symbols = symbol_index(tokens, config);
+
     tokens
         .iter()
-        .filter(|tok| matches!(tok.kind, TokenKind::Gear))
-        .filter_map(|t| calculate_gear(&tokens, t))
+        .filter(|tok| is_part_number(&symbols, tok))
+        .filter_map(|tok| tok.kind.value())
         .sum()
 }
 
+fn calculate2(tokens: &[Token], config: &Config) -> usize {
+    let index = number_index(tokens);
+
+    tokens
+        .iter()
+        .filter(|tok| tok.kind.is_gear(config))
+        .filter_map(|t| calculate_gear(&index, t, config))
+        .sum()
+}
+
+/// Removes `--render <format>` from `options`, leaving the rest to `Config`.
+fn take_render(options: &mut Vec<String>) -> Result<Option<render::Format>, String> {
+    let Some(i) = options.iter().position(|arg| arg == "--render") else {
+        return Ok(None);
+    };
+
+    if i + 1 == options.len() {
+        return Err("missing value for --render".to_string());
+    }
+
+    let format = options.remove(i + 1).parse()?;
+    options.remove(i);
+    Ok(Some(format))
+}
+
 fn main() {
     let mut args = args();
     args.next();
     let path = args.next().unwrap();
-    let src = fs::read_to_string(path).unwrap();
+
+    let mut options = args.collect::<Vec<_>>();
+    let format = take_render(&mut options).unwrap();
+
+    let config = Config::from_args(options.into_iter()).unwrap();
+    let src = input::read(path);
     let tokens = parse(&src);
-    let res = calculate(tokens);
 
-    dbg!(res);
+    if let Some(format) = format {
+        print!("{}", render::render(&tokens, &config, format));
+        return;
+    }
+
+    println!("Part 1: {}", calculate1(&tokens, &config));
+    println!("Part 2: {}", calculate2(&tokens, &config));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use config::{Arity, CharClass, Combine};
+
+    #[test]
+    fn test_part1() {
+        let src = input::read("test.txt");
+        assert_eq!(calculate1(&parse(&src), &Config::default()), 4361);
+    }
+
+    #[test]
+    fn test_part2() {
+        let src = input::read("test.txt");
+        assert_eq!(calculate2(&parse(&src), &Config::default()), 467835);
+    }
+
+    #[test]
+    fn test_symbols() {
+        let tokens = parse("1.2.3\n#.%.-\n");
+
+        assert_eq!(calculate1(&tokens, &Config::default()), 6);
+        assert_eq!(calculate1(&parse("1.2\n...\n"), &Config::default()), 0);
+    }
+
+    #[test]
+    fn test_number_ids() {
+        let ids = parse("12.3\n4..56")
+            .iter()
+            .filter_map(|tok| match tok.kind {
+                TokenKind::Number { id, value } => Some((id, value)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(ids, [(0, 12), (1, 3), (2, 4), (3, 56)]);
+    }
+
+    #[test]
+    fn test_gear_on_edges() {
+        assert_eq!(calculate2(&parse("*2\n3.\n"), &Config::default()), 6);
+        assert_eq!(calculate2(&parse(".2\n3*\n"), &Config::default()), 6);
+        assert_eq!(calculate2(&parse("2.\n*3"), &Config::default()), 6);
+        assert_eq!(calculate1(&parse("*.\n.7"), &Config::default()), 7);
+    }
+
+    #[test]
+    fn test_gear_touching_number_twice() {
+        // 123 touches the gear through all three cells above it
+        assert_eq!(calculate2(&parse("123\n.*.\n"), &Config::default()), 0);
+        assert_eq!(
+            calculate2(&parse("123\n.*.\n..4\n"), &Config::default()),
+            492
+        );
+    }
+
+    #[test]
+    fn test_gear_equal_numbers() {
+        assert_eq!(calculate2(&parse("2*2\n"), &Config::default()), 4);
+        assert_eq!(calculate2(&parse("2..\n.*.\n..2\n"), &Config::default()), 4);
+    }
+
+    #[test]
+    fn test_config() {
+        let config = Config {
+            gears: CharClass::Only(vec!['#']),
+            arity: Arity::AtLeast(2),
+            combine: Combine::Sum,
+            ..Config::default()
+        };
+
+        assert_eq!(calculate2(&parse("2#3\n.4.\n"), &config), 9);
+        assert_eq!(calculate2(&parse("2*3\n"), &config), 0);
+
+        let config = Config {
+            symbols: CharClass::Only(vec!['$']),
+            gears: CharClass::Only(vec![]),
+            ..Config::default()
+        };
+
+        assert_eq!(calculate1(&parse("1$.\n..2\n3#.\n"), &config), 3);
+    }
+
+    #[test]
+    fn test_take_render() {
+        let mut options = ["--arity", "3", "--render", "html"]
+            .map(String::from)
+            .to_vec();
+        assert_eq!(take_render(&mut options), Ok(Some(render::Format::Html)));
+        assert_eq!(options, ["--arity", "3"]);
+
+        let mut options = vec!["--render".to_string()];
+        assert_eq!(
+            take_render(&mut options),
+            Err("missing value for --render".to_string())
+        );
+        assert_eq!(take_render(&mut vec![]), Ok(None));
+    }
 }
@@ -0,0 +1,179 @@
+use std::str::FromStr;
+
+use crate::{calculate_gear, is_part_number, number_index, symbol_index, Config, Token, TokenKind};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Ansi,
+    Html,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ansi" => Ok(Format::Ansi),
+            "html" => Ok(Format::Html),
+            s => Err(format!("{s}: expected ansi or html")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Style {
+    Plain,
+    Part,
+    Ignored,
+    Gear,
+    BrokenGear,
+}
+
+impl Style {
+    fn ansi(&self) -> &'static str {
+        match self {
+            Style::Plain => "",
+            Style::Part => "\x1b[32m",
+            Style::Ignored => "\x1b[31m",
+            Style::Gear => "\x1b[1;33m",
+            Style::BrokenGear => "\x1b[35m",
+        }
+    }
+
+    fn class(&self) -> &'static str {
+        match self {
+            Style::Plain => "plain",
+            Style::Part => "part",
+            Style::Ignored => "ignored",
+            Style::Gear => "gear",
+            Style::BrokenGear => "broken-gear",
+        }
+    }
+}
+
+const HTML_HEADER: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Engine schematic</title>
+<style>
+body { background: #0f0f23; color: #666; }
+pre { font-family: monospace; }
+.part { color: #00cc00; }
+.ignored { color: #ff5555; }
+.gear { color: #ffff66; font-weight: bold; }
+.broken-gear { color: #cc66cc; }
+</style>
+</head>
+<body>
+<p>
+<span class="part">part number</span>
+<span class="ignored">ignored number</span>
+<span class="gear">gear</span>
+<span class="broken-gear">not a gear</span>
+</p>
+<pre>
+"#;
+
+const HTML_FOOTER: &str = "</pre>\n</body>\n</html>\n";
+
+fn text(tok: &Token) -> String {
+    match tok.kind {
+        TokenKind::Number { value, .. } => {
+            format!("{value:0width$}", width = tok.end - tok.start + 1)
+        }
+        TokenKind::Digit(c) | TokenKind::Symbol(c) => c.to_string(),
+        TokenKind::Dot => ".".to_string(),
+        TokenKind::Unknown => String::new(),
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Renders the schematic from `tokens`, coloring part numbers, ignored
+/// numbers, gears and gear symbols that are not adjacent to enough numbers.
+pub fn render(tokens: &[Token], config: &Config, format: Format) -> String {
+    let symbols = symbol_index(tokens, config);
+    let numbers = number_index(tokens);
+
+    let mut out = String::new();
+    if format == Format::Html {
+        out.push_str(HTML_HEADER);
+    }
+
+    let mut line = 0;
+    let mut pos = 0;
+
+    for tok in tokens {
+        while line < tok.line {
+            out.push('\n');
+            line += 1;
+            pos = 0;
+        }
+
+        out.push_str(&" ".repeat(tok.start.saturating_sub(pos)));
+        pos = tok.end + 1;
+
+        let style = match tok.kind {
+            TokenKind::Number { .. } if is_part_number(&symbols, tok) => Style::Part,
+            TokenKind::Number { .. } => Style::Ignored,
+            _ if tok.kind.is_gear(config) => match calculate_gear(&numbers, tok, config) {
+                Some(_) => Style::Gear,
+                None => Style::BrokenGear,
+            },
+            _ => Style::Plain,
+        };
+
+        let text = text(tok);
+        let styled = match (format, style) {
+            (Format::Ansi, Style::Plain) => text,
+            (Format::Ansi, style) => format!("{}{text}\x1b[0m", style.ansi()),
+            (Format::Html, Style::Plain) => escape(&text),
+            (Format::Html, style) => {
+                format!("<span class=\"{}\">{}</span>", style.class(), escape(&text))
+            }
+        };
+
+        out.push_str(&styled);
+    }
+
+    out.push('\n');
+    if format == Format::Html {
+        out.push_str(HTML_FOOTER);
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+
+    #[test]
+    fn test_render_ansi() {
+        let tokens = parse("12.3\n.*..\n4..5\n");
+
+        assert_eq!(
+            render(&tokens, &Config::default(), Format::Ansi),
+            "\x1b[32m12\x1b[0m.\x1b[31m3\x1b[0m\n\
+             .\x1b[1;33m*\x1b[0m..\n\
+             \x1b[32m4\x1b[0m..\x1b[31m5\x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn test_render_html() {
+        let tokens = parse("1<*\n");
+        let html = render(&tokens, &Config::default(), Format::Html);
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains(
+            "<span class=\"part\">1</span>&lt;<span class=\"broken-gear\">*</span>\n</pre>"
+        ));
+    }
+}
@@ -0,0 +1,97 @@
+use std::time::Instant;
+
+use chumsky::Parser;
+
+use crate::{parser, Card};
+
+/// xorshift64, good enough to generate cards.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self, max: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % max as u64) as usize
+    }
+}
+
+/// Generates `cards` cards with `winning` distinct winning numbers and `nums`
+/// drawn numbers, which may repeat, all in `1..100`.
+pub fn generate(cards: usize, winning: usize, nums: usize) -> String {
+    let mut rng = Rng(0x2023_1204);
+    let mut out = String::new();
+
+    let mut draw = |count: usize, distinct: bool| {
+        let mut drawn: Vec<usize> = vec![];
+        while drawn.len() < if distinct { count.min(99) } else { count } {
+            let num = 1 + rng.next(99);
+            if !distinct || !drawn.contains(&num) {
+                drawn.push(num);
+            }
+        }
+
+        drawn
+            .iter()
+            .map(|n| format!("{n:>2}"))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    for id in 1..=cards {
+        let winning = draw(winning, true);
+        let nums = draw(nums, false);
+        out.push_str(&format!("Card {id}: {winning} | {nums}\n"));
+    }
+
+    out
+}
+
+/// Matching as it was done before cards carried a bitset.
+fn matching_vec(card: &Card) -> usize {
+    card.nums
+        .iter()
+        .filter(|num| card.winning_nums.contains(num))
+        .count()
+}
+
+pub fn run(cards: usize, winning: usize, nums: usize) {
+    let src = generate(cards, winning, nums);
+    let cards = parser().parse(src).unwrap();
+
+    let start = Instant::now();
+    let vec: usize = cards.iter().map(matching_vec).sum();
+    let vec_time = start.elapsed();
+
+    let start = Instant::now();
+    let bitset: usize = cards.iter().map(|c| c.matching()).sum();
+    let bitset_time = start.elapsed();
+
+    assert_eq!(vec, bitset);
+
+    println!("{} cards, {vec} matching numbers", cards.len());
+    println!("  Vec::contains: {vec_time:?}");
+    println!("  bitset:        {bitset_time:?}");
+    println!(
+        "  speedup:       {:.1}x",
+        vec_time.as_secs_f64() / bitset_time.as_secs_f64()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate() {
+        let cards = parser().parse(generate(50, 10, 25)).unwrap();
+
+        assert_eq!(cards.len(), 50);
+        assert_eq!(cards[49].id, 50);
+        assert!(cards.iter().all(|c| c.winning_nums.len() == 10));
+        assert!(cards.iter().all(|c| matching_vec(c) == c.matching()));
+        assert!(cards
+            .iter()
+            .any(|c| (1..c.nums.len()).any(|i| c.nums[..i].contains(&c.nums[i]))));
+    }
+}
@@ -0,0 +1,60 @@
+/// A set of numbers stored as a `u128` bitmap, with a sorted fallback for
+/// numbers that do not fit in it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NumberSet {
+    small: u128,
+    large: Vec<usize>,
+}
+
+impl NumberSet {
+    pub fn insert(&mut self, num: usize) {
+        if num < u128::BITS as usize {
+            self.small |= 1 << num;
+        } else if let Err(pos) = self.large.binary_search(&num) {
+            self.large.insert(pos, num);
+        }
+    }
+
+    pub fn contains(&self, num: usize) -> bool {
+        if num < u128::BITS as usize {
+            self.small & 1 << num != 0
+        } else {
+            self.large.binary_search(&num).is_ok()
+        }
+    }
+}
+
+impl FromIterator<usize> for NumberSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = NumberSet::default();
+        for num in iter {
+            set.insert(num);
+        }
+
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert() {
+        let set = [1000, 0, 5, 127, 128, 1000]
+            .into_iter()
+            .collect::<NumberSet>();
+
+        assert_eq!(set.small, 1 | 1 << 5 | 1 << 127);
+        assert_eq!(set.large, [128, 1000]);
+    }
+
+    #[test]
+    fn test_contains() {
+        let set = [1, 2, 3, 200, 300].into_iter().collect::<NumberSet>();
+
+        assert!(set.contains(1) && set.contains(3) && set.contains(300));
+        assert!(!set.contains(0) && !set.contains(127) && !set.contains(400));
+        assert!(!NumberSet::default().contains(5));
+    }
+}
@@ -0,0 +1,169 @@
+use serde::Serialize;
+
+use crate::{Card, Scratchcards};
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct Node {
+    pub id: usize,
+    pub instances: usize,
+}
+
+/// Every instance of card `from` gives one copy of card `to`.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct Edge {
+    pub from: usize,
+    pub to: usize,
+    pub instances: usize,
+}
+
+/// How copies propagate between cards, built from cards on which
+/// `calculate2` already ran.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct Cascade {
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+}
+
+impl Cascade {
+    pub fn new(cards: &[Card]) -> Self {
+        let nodes = cards
+            .iter()
+            .map(|card| Node {
+                id: card.id,
+                instances: card.instances,
+            })
+            .collect();
+
+        let edges = cards
+            .iter()
+            .enumerate()
+            .flat_map(|(i, card)| {
+                cards.won_cards(i).map(move |j| Edge {
+                    from: card.id,
+                    to: cards[j].id,
+                    instances: card.instances,
+                })
+            })
+            .collect();
+
+        Self { nodes, edges }
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph cascade {\n");
+
+        for Node { id, instances } in &self.nodes {
+            out.push_str(&format!("  {id} [label=\"Card {id}\\n{instances}\"];\n"));
+        }
+
+        for Edge {
+            from,
+            to,
+            instances,
+        } in &self.edges
+        {
+            out.push_str(&format!(
+                "  {from} -> {to} [weight={instances}, label=\"{instances}\"];\n"
+            ));
+        }
+
+        out.push_str("}\n");
+        out
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    /// Copies contributed by each card, highest first.
+    pub fn contributions(&self) -> Vec<(usize, usize)> {
+        let mut contributions = self
+            .nodes
+            .iter()
+            .map(|node| {
+                let copies: usize = self
+                    .edges
+                    .iter()
+                    .filter(|edge| edge.from == node.id)
+                    .map(|edge| edge.instances)
+                    .sum();
+
+                (node.id, copies)
+            })
+            .collect::<Vec<_>>();
+
+        contributions.sort_by(|(a_id, a), (b_id, b)| b.cmp(a).then(a_id.cmp(b_id)));
+        contributions
+    }
+
+    pub fn summary(&self, top: usize) -> String {
+        let contributions = self.contributions();
+        let total: usize = contributions.iter().map(|(_, copies)| copies).sum();
+
+        let mut out = format!("{total} copies won, top {top} contributors:\n");
+        for (id, copies) in contributions.iter().take(top) {
+            out.push_str(&format!("  Card {id}: {copies} copies\n"));
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chumsky::Parser;
+
+    use super::*;
+    use crate::parser;
+
+    fn cascade() -> Cascade {
+        let mut cards = parser().parse(input::read("test1.txt")).unwrap();
+        cards.calculate2().unwrap();
+
+        Cascade::new(&cards)
+    }
+
+    #[test]
+    fn test_cascade() {
+        let cascade = cascade();
+
+        assert_eq!(cascade.nodes.len(), 6);
+        assert_eq!(cascade.edges.len(), 4 + 2 + 2 + 1);
+        assert_eq!(
+            cascade.edges[4],
+            Edge {
+                from: 2,
+                to: 3,
+                instances: 2
+            }
+        );
+        assert_eq!(
+            cascade.contributions()[..3],
+            [(3, 8), (4, 8), (1, 4)],
+            "card 3 has 4 instances winning 2 cards"
+        );
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let dot = cascade().to_dot();
+
+        assert!(dot.starts_with("digraph cascade {\n"));
+        assert!(dot.contains("  5 [label=\"Card 5\\n14\"];\n"));
+        assert!(dot.contains("  4 -> 5 [weight=8, label=\"8\"];\n"));
+    }
+
+    #[test]
+    fn test_to_json() {
+        let json: serde_json::Value = serde_json::from_str(&cascade().to_json()).unwrap();
+
+        assert_eq!(
+            json["nodes"][4],
+            serde_json::json!({ "id": 5, "instances": 14 })
+        );
+        assert_eq!(
+            json["edges"][0],
+            serde_json::json!({ "from": 1, "to": 2, "instances": 1 })
+        );
+    }
+}
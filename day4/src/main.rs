@@ -1,16 +1,24 @@
-use std::{env::args, fs};
+use std::{env, env::args, ops::Range, process};
 
-use chumsky::{chain::Chain, prelude::*};
+use bitset::NumberSet;
+use chumsky::prelude::*;
 
-#[derive(Debug, Clone)]
+mod bench;
+mod bitset;
+mod cascade;
+mod printer;
+mod validate;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 struct Card {
     id: usize,
     nums: Vec<usize>,
     winning_nums: Vec<usize>,
+    winning: NumberSet,
     instances: usize,
 }
 
-fn parser() -> impl Parser<char, Vec<Card>, Error = Simple<char>> {
+fn card_parser() -> impl Parser<char, Card, Error = Simple<char>> {
     let digit = text::int(10)
         .padded()
         .map(|c: String| c.parse::<usize>().unwrap());
@@ -19,53 +27,66 @@ fn parser() -> impl Parser<char, Vec<Card>, Error = Simple<char>> {
 
     let nums = digit
         .repeated()
-        .then_ignore(just('|'))
+        .then_ignore(just('|').padded())
         .then(digit.repeated());
 
     id.then(nums)
-        .map(|(id, (winning_nums, nums))| Card {
-            id,
-            winning_nums,
-            nums,
-            instances: 1,
-        })
-        .repeated()
+        .map(|(id, (winning_nums, nums))| Card::new(id, winning_nums, nums))
+}
+
+fn parser() -> impl Parser<char, Vec<Card>, Error = Simple<char>> {
+    card_parser().repeated()
 }
 
 trait Calculated {
     fn calculate1(&self) -> usize;
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CascadeError {
+    /// The number of instances of a card does not fit in a `usize`.
+    Overflow { card: usize },
+    /// Every card fits but their sum does not.
+    TotalOverflow,
+}
+
 trait Scratchcards {
     // fn get_original(index: &usize) -> Option<&Card>;
-    fn calculate2(&mut self) -> usize;
+    fn won_cards(&self, index: usize) -> Range<usize>;
+    fn calculate2(&mut self) -> Result<usize, CascadeError>;
+}
+
+fn tracing() -> bool {
+    env::var_os("AOC_TRACE").is_some()
 }
 
 impl Card {
-    fn is_winning(&self, num: &usize) -> bool {
-        self.winning_nums.iter().any(|u| u == num)
+    fn new(id: usize, winning_nums: Vec<usize>, nums: Vec<usize>) -> Self {
+        Self {
+            id,
+            winning: winning_nums.iter().copied().collect(),
+            winning_nums,
+            nums,
+            instances: 1,
+        }
     }
 
-    fn matching_numbers(&self) -> impl Iterator<Item = &usize> {
+    /// Number of drawn numbers that are winning, a number drawn twice counting
+    /// twice.
+    fn matching(&self) -> usize {
         self.nums
             .iter()
-            .filter(|item| self.winning_nums.contains(item))
+            .filter(|num| self.winning.contains(**num))
+            .count()
     }
 }
 
 impl Calculated for Card {
     fn calculate1(&self) -> usize {
-        let mut score = 0;
-
-        for num in &self.nums {
-            if !self.is_winning(num) {
-                continue;
-            }
-
-            score += if score == 0 { 1 } else { score };
+        match self.matching() {
+            0 => 0,
+            matching => 1 << (matching - 1),
         }
-
-        score
     }
 }
 
@@ -75,23 +96,59 @@ impl Calculated for Vec<Card> {
     }
 }
 
-impl Scratchcards for Vec<Card> {
-    fn calculate2(&mut self) -> usize {
-        for i in 0..self.iter().count().clone() {
-            let card = self.get(i).unwrap();
-            let instances = card.instances;
-            let winning = card.matching_numbers().count();
+impl Scratchcards for [Card] {
+    /// Indices of the cards copied by each instance of the card at `index`.
+    /// Copies won past the last card are dropped, as if the table was clamped
+    /// to the end of the list.
+    fn won_cards(&self, index: usize) -> Range<usize> {
+        let end = (index + 1 + self[index].matching()).min(self.len());
+        index + 1..end.max(index + 1)
+    }
 
-            println!("Card {}: Got {} matching numbers", card.id, winning);
+    fn calculate2(&mut self) -> Result<usize, CascadeError> {
+        let len = self.len();
+        let trace = tracing();
 
-            for ii in 0..winning {
-                let dup_card = self.get_mut(1 + i + ii).unwrap();
-                println!("  Copy {}", dup_card.id);
-                dup_card.instances += instances;
+        // Difference array: `copies[i]` instances start being added at card
+        // `i` and `expired[i]` stop being added at card `i`.
+        let mut copies = vec![0usize; len + 1];
+        let mut expired = vec![0usize; len + 1];
+        let mut running = 0usize;
+
+        for i in 0..len {
+            let won = self.won_cards(i);
+            let card = &mut self[i];
+            let overflow = CascadeError::Overflow { card: card.id };
+
+            // `expired[i]` was added to `running` earlier, so it never
+            // underflows
+            running = (running - expired[i])
+                .checked_add(copies[i])
+                .ok_or(overflow)?;
+            card.instances = card.instances.checked_add(running).ok_or(overflow)?;
+
+            if trace {
+                println!(
+                    "Card {}: {} instances, got {} matching numbers",
+                    card.id,
+                    card.instances,
+                    card.matching()
+                );
+            }
+
+            if !won.is_empty() {
+                copies[won.start] = copies[won.start]
+                    .checked_add(card.instances)
+                    .ok_or(overflow)?;
+                expired[won.end] = expired[won.end]
+                    .checked_add(card.instances)
+                    .ok_or(overflow)?;
             }
         }
 
-        self.iter().map(|c| c.instances).sum()
+        self.iter()
+            .try_fold(0usize, |total, card| total.checked_add(card.instances))
+            .ok_or(CascadeError::TotalOverflow)
     }
 }
 
@@ -100,10 +157,115 @@ fn main() {
     args.next();
 
     let path = args.next().unwrap();
-    let src = fs::read_to_string(path).unwrap();
+    if path == "bench" {
+        let mut arg = |default| args.next().map_or(default, |a| a.parse().unwrap());
+        bench::run(arg(100_000), arg(10), arg(25));
+        return;
+    }
+
+    let src = input::read(path);
+
+    let validation = match input::Mode::from_env() {
+        input::Mode::Strict => validate::Validation::strict(),
+        input::Mode::Lenient => validate::Validation::default(),
+    };
+
+    let diagnostics = validate::validate(&src, &validation);
+    for diagnostic in &diagnostics {
+        eprintln!("{diagnostic}");
+    }
+
+    if diagnostics.iter().any(|d| d.level == validate::Level::Deny) {
+        process::exit(1);
+    }
 
     let mut cards = parser().parse(src).unwrap();
 
-    dbg!(cards.calculate1());
-    dbg!(cards.calculate2());
+    match args.next().as_deref() {
+        Some("print") => {
+            let mut arg = |default| args.next().map_or(default, |a| a.parse().unwrap());
+            let (from, to) = (arg(1), arg(usize::MAX));
+
+            cards.retain(|c| (from..=to).contains(&c.id));
+            print!("{}", printer::print(&cards));
+        }
+        Some(format) => {
+            cards.calculate2().unwrap();
+            let cascade = cascade::Cascade::new(&cards);
+
+            match format {
+                "dot" => print!("{}", cascade.to_dot()),
+                "json" => println!("{}", cascade.to_json()),
+                "summary" => print!("{}", cascade.summary(10)),
+                format => panic!("Unknown format {format}, expected dot, json or summary"),
+            }
+        }
+        None => {
+            dbg!(cards.calculate1());
+            dbg!(cards.calculate2().unwrap());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part1() {
+        let cards = parser().parse(input::read("test1.txt")).unwrap();
+        assert_eq!(cards.calculate1(), 13);
+    }
+
+    #[test]
+    fn test_part2() {
+        let mut cards = parser().parse(input::read("test1.txt")).unwrap();
+        assert_eq!(cards.calculate2(), Ok(30));
+    }
+
+    #[test]
+    fn test_duplicate_drawn() {
+        // 5 is drawn twice and counts twice, as it always has
+        let card = Card::new(1, vec![5, 7], vec![5, 5, 9]);
+
+        assert_eq!(card.matching(), 2);
+        assert_eq!(card.calculate1(), 2);
+    }
+
+    #[test]
+    fn test_part2_clamped() {
+        // The last card wins 2 copies but there is no card after it
+        let mut cards = [
+            Card::new(1, vec![1], vec![1]),
+            Card::new(2, vec![1, 2], vec![1, 2]),
+        ];
+
+        assert_eq!(cards.calculate2(), Ok(3));
+        assert_eq!(cards[1].instances, 2);
+    }
+
+    #[test]
+    fn test_part2_expired_before_added() {
+        // Card 1's copies stop as card 2's start at card 3, both together
+        // would overflow but card 3 only gets card 2's
+        let mut cards = [
+            Card::new(1, vec![1], vec![1]),
+            Card::new(2, vec![1], vec![1]),
+            Card::new(3, vec![], vec![]),
+        ];
+        cards[0].instances = usize::MAX / 2 + 1;
+
+        // Only the total overflows
+        assert_eq!(cards.calculate2(), Err(CascadeError::TotalOverflow));
+        assert_eq!(cards[2].instances, usize::MAX / 2 + 3);
+    }
+
+    #[test]
+    fn test_part2_overflow() {
+        let mut cards = [Card::new(1, vec![1], vec![1]), Card::new(2, vec![], vec![])];
+        cards[0].instances = usize::MAX;
+        cards[1].instances = 1;
+
+        assert_eq!(cards.calculate2(), Err(CascadeError::Overflow { card: 2 }));
+    }
 }
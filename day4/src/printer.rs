@@ -0,0 +1,69 @@
+use crate::Card;
+
+fn digits(num: usize) -> usize {
+    num.to_string().len()
+}
+
+/// Prints cards in the puzzle format, with ids and numbers right-aligned so
+/// that every column lines up.
+pub fn print(cards: &[Card]) -> String {
+    let id_width = cards.iter().map(|c| digits(c.id)).max().unwrap_or(1);
+    let num_width = cards
+        .iter()
+        .flat_map(|c| c.winning_nums.iter().chain(&c.nums))
+        .map(|n| digits(*n))
+        .max()
+        .unwrap_or(1);
+
+    let list = |nums: &[usize]| {
+        nums.iter()
+            .map(|n| format!(" {n:>num_width$}"))
+            .collect::<String>()
+    };
+
+    let mut out = String::new();
+    for card in cards {
+        out.push_str(&format!(
+            "Card {:>id_width$}:{} |{}\n",
+            card.id,
+            list(&card.winning_nums),
+            list(&card.nums)
+        ));
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use chumsky::Parser;
+
+    use super::*;
+    use crate::parser;
+
+    #[test]
+    fn test_print_input() {
+        let src = input::read("test1.txt");
+        let cards = parser().parse(src.clone()).unwrap();
+
+        assert_eq!(print(&cards), src);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let cards = vec![
+            Card::new(1, vec![1, 200], vec![3]),
+            Card::new(10, vec![], vec![4, 5]),
+            Card::new(11, vec![6], vec![]),
+        ];
+
+        let printed = print(&cards);
+        assert_eq!(
+            printed,
+            "Card  1:   1 200 |   3\n\
+             Card 10: |   4   5\n\
+             Card 11:   6 |\n"
+        );
+        assert_eq!(parser().parse(printed).unwrap(), cards);
+    }
+}
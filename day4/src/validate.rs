@@ -0,0 +1,276 @@
+use std::fmt;
+
+use chumsky::{primitive::end, Parser};
+
+use crate::{card_parser, Card};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Allow,
+    Warn,
+    Deny,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum List {
+    Winning,
+    Drawn,
+}
+
+impl fmt::Display for List {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            List::Winning => write!(f, "winning"),
+            List::Drawn => write!(f, "drawn"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Kind {
+    Invalid,
+    DuplicateNumber {
+        list: List,
+        num: usize,
+    },
+    NonSequentialId {
+        expected: usize,
+        found: usize,
+    },
+    ListSize {
+        list: List,
+        expected: usize,
+        found: usize,
+    },
+}
+
+/// Lines start at 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub line: usize,
+    pub level: Level,
+    pub kind: Kind,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let level = match self.level {
+            Level::Deny => "error",
+            _ => "warning",
+        };
+
+        write!(f, "{level}: line {}: ", self.line)?;
+
+        match &self.kind {
+            Kind::Invalid => write!(f, "not a card"),
+            Kind::DuplicateNumber { list, num } => {
+                write!(f, "{num} appears more than once in the {list} numbers")
+            }
+            Kind::NonSequentialId { expected, found } => {
+                write!(f, "expected card {expected}, found card {found}")
+            }
+            Kind::ListSize {
+                list,
+                expected,
+                found,
+            } => write!(f, "{found} {list} numbers, the first card has {expected}"),
+        }
+    }
+}
+
+/// How each check is reported. Cards that cannot be parsed are always denied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Validation {
+    pub duplicates: Level,
+    pub ids: Level,
+    pub sizes: Level,
+}
+
+impl Default for Validation {
+    fn default() -> Self {
+        Self {
+            duplicates: Level::Warn,
+            ids: Level::Warn,
+            sizes: Level::Warn,
+        }
+    }
+}
+
+impl Validation {
+    pub fn strict() -> Self {
+        Self {
+            duplicates: Level::Deny,
+            ids: Level::Deny,
+            sizes: Level::Deny,
+        }
+    }
+}
+
+fn duplicates(nums: &[usize]) -> Vec<usize> {
+    let mut found = vec![];
+
+    for (i, num) in nums.iter().enumerate() {
+        if nums[..i].contains(num) && !found.contains(num) {
+            found.push(*num);
+        }
+    }
+
+    found
+}
+
+pub fn validate(src: &str, validation: &Validation) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    let mut first: Option<Card> = None;
+    let mut expected = 1;
+
+    for (i, line) in src.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let mut report = |level, kind| {
+            if level != Level::Allow {
+                diagnostics.push(Diagnostic {
+                    line: i + 1,
+                    level,
+                    kind,
+                });
+            }
+        };
+
+        let Ok(card) = card_parser().then_ignore(end()).parse(line) else {
+            report(Level::Deny, Kind::Invalid);
+            continue;
+        };
+
+        for (list, nums) in [
+            (List::Winning, &card.winning_nums),
+            (List::Drawn, &card.nums),
+        ] {
+            for num in duplicates(nums) {
+                report(validation.duplicates, Kind::DuplicateNumber { list, num });
+            }
+        }
+
+        if card.id != expected {
+            report(
+                validation.ids,
+                Kind::NonSequentialId {
+                    expected,
+                    found: card.id,
+                },
+            );
+        }
+        expected = card.id + 1;
+
+        if let Some(first) = &first {
+            let sizes = [
+                (
+                    List::Winning,
+                    first.winning_nums.len(),
+                    card.winning_nums.len(),
+                ),
+                (List::Drawn, first.nums.len(), card.nums.len()),
+            ];
+
+            for (list, expected, found) in sizes {
+                if expected != found {
+                    report(
+                        validation.sizes,
+                        Kind::ListSize {
+                            list,
+                            expected,
+                            found,
+                        },
+                    );
+                }
+            }
+        } else {
+            first = Some(card);
+        }
+    }
+
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid() {
+        let src = input::read("test1.txt");
+        assert_eq!(validate(&src, &Validation::strict()), []);
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        let src = "Card 1: 1 2 | 3 4\n\
+                   Card 2: 5 5 | 3 3 3\n\
+                   Card 4: 1 2 | 3 4\n\
+                   Card x: 1 | 2\n";
+
+        assert_eq!(
+            validate(src, &Validation::default()),
+            [
+                Diagnostic {
+                    line: 2,
+                    level: Level::Warn,
+                    kind: Kind::DuplicateNumber {
+                        list: List::Winning,
+                        num: 5
+                    }
+                },
+                Diagnostic {
+                    line: 2,
+                    level: Level::Warn,
+                    kind: Kind::DuplicateNumber {
+                        list: List::Drawn,
+                        num: 3
+                    }
+                },
+                Diagnostic {
+                    line: 2,
+                    level: Level::Warn,
+                    kind: Kind::ListSize {
+                        list: List::Drawn,
+                        expected: 2,
+                        found: 3
+                    }
+                },
+                Diagnostic {
+                    line: 3,
+                    level: Level::Warn,
+                    kind: Kind::NonSequentialId {
+                        expected: 3,
+                        found: 4
+                    }
+                },
+                Diagnostic {
+                    line: 4,
+                    level: Level::Deny,
+                    kind: Kind::Invalid
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_levels() {
+        let src = "Card 1: 1 1 | 2\nCard 3: 1 2 | 2";
+        let validation = Validation {
+            duplicates: Level::Allow,
+            ids: Level::Deny,
+            sizes: Level::Warn,
+        };
+
+        let diagnostics = validate(src, &validation);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].level, Level::Deny);
+        assert_eq!(
+            diagnostics[0].to_string(),
+            "error: line 2: expected card 2, found card 3"
+        );
+    }
+}
@@ -0,0 +1,258 @@
+use std::{fmt, ops::Range};
+
+use crate::{merge_ranges, Almanac, Map};
+
+/// Ids in `source` are mapped to `id + offset`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    pub source: Range<usize>,
+    pub offset: i64,
+}
+
+impl Segment {
+    pub fn apply(&self, id: usize) -> usize {
+        (id as i64 + self.offset) as usize
+    }
+
+    fn image(&self) -> Range<usize> {
+        self.apply(self.source.start)..self.apply(self.source.end)
+    }
+}
+
+/// A piecewise-linear function over every id, as a sorted list of segments
+/// partitioning `0..usize::MAX`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Composed {
+    pub segments: Vec<Segment>,
+}
+
+impl Map {
+    /// The map as segments, including identity segments for the gaps.
+    pub fn segments(&self) -> Vec<Segment> {
+        let mut segments = vec![];
+        let mut unmapped = vec![Range {
+            start: 0,
+            end: usize::MAX,
+        }];
+
+        // Ranges listed first win over the ones they overlap
+        for mr in &self.ranges {
+            let mut rest = vec![];
+
+            for range in unmapped {
+                let start = range.start.max(mr.source.start);
+                let end = range.end.min(mr.source.end);
+
+                if start >= end {
+                    rest.push(range);
+                    continue;
+                }
+
+                segments.push(Segment {
+                    source: start..end,
+                    offset: mr.destination.start as i64 - mr.source.start as i64,
+                });
+
+                if range.start < start {
+                    rest.push(range.start..start);
+                }
+                if end < range.end {
+                    rest.push(end..range.end);
+                }
+            }
+
+            unmapped = rest;
+        }
+
+        segments.extend(
+            unmapped
+                .into_iter()
+                .map(|source| Segment { source, offset: 0 }),
+        );
+        normalize(segments)
+    }
+}
+
+/// Sorts segments and merges neighbours sharing the same offset.
+fn normalize(mut segments: Vec<Segment>) -> Vec<Segment> {
+    segments.retain(|s| !s.source.is_empty());
+    segments.sort_by_key(|s| s.source.start);
+
+    let mut merged: Vec<Segment> = vec![];
+    for segment in segments {
+        match merged.last_mut() {
+            Some(last)
+                if last.source.end == segment.source.start && last.offset == segment.offset =>
+            {
+                last.source.end = segment.source.end
+            }
+            _ => merged.push(segment),
+        }
+    }
+
+    merged
+}
+
+/// Image of every id in `ranges` through sorted `segments` covering them.
+pub fn map_ranges(segments: &[Segment], ranges: &[Range<usize>]) -> Vec<Range<usize>> {
+    let mut mapped = vec![];
+
+    for range in ranges {
+        let first = segments.partition_point(|s| s.source.end <= range.start);
+
+        for segment in segments[first..]
+            .iter()
+            .take_while(|s| s.source.start < range.end)
+        {
+            let start = range.start.max(segment.source.start);
+            let end = range.end.min(segment.source.end);
+            mapped.push(segment.apply(start)..segment.apply(end));
+        }
+    }
+
+    merge_ranges(mapped)
+}
+
+impl Composed {
+    pub fn identity() -> Self {
+        Self {
+            segments: vec![Segment {
+                source: 0..usize::MAX,
+                offset: 0,
+            }],
+        }
+    }
+
+    /// `self` followed by `next`.
+    pub fn then(&self, next: &[Segment]) -> Self {
+        let mut segments = vec![];
+
+        for segment in &self.segments {
+            let image = segment.image();
+            let first = next.partition_point(|s| s.source.end <= image.start);
+
+            for n in next[first..]
+                .iter()
+                .take_while(|s| s.source.start < image.end)
+            {
+                let start = image.start.max(n.source.start);
+                let end = image.end.min(n.source.end);
+
+                segments.push(Segment {
+                    source: (start as i64 - segment.offset) as usize
+                        ..(end as i64 - segment.offset) as usize,
+                    offset: segment.offset + n.offset,
+                });
+            }
+        }
+
+        Self {
+            segments: normalize(segments),
+        }
+    }
+
+    /// `usize::MAX` is past the last segment and, like in `Map::get`, kept
+    /// as is.
+    pub fn get(&self, id: usize) -> usize {
+        let i = self.segments.partition_point(|s| s.source.end <= id);
+        self.segments.get(i).map_or(id, |s| s.apply(id))
+    }
+
+    /// Image of every id in `ranges`.
+    pub fn map_ranges(&self, ranges: &[Range<usize>]) -> Vec<Range<usize>> {
+        map_ranges(&self.segments, ranges)
+    }
+}
+
+impl fmt::Display for Composed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for Segment { source, offset } in &self.segments {
+            writeln!(f, "{:>12}..{:<12} {offset:+}", source.start, source.end)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Almanac {
+    /// Folds every map into a single function from the first category to
+    /// the last one.
+    pub fn compose(&self) -> Composed {
+        self.maps
+            .iter()
+            .fold(Composed::identity(), |composed, map| {
+                composed.then(&map.index)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chumsky::Parser;
+
+    use super::*;
+    use crate::parser;
+
+    #[test]
+    fn test_segments() {
+        let almanac = parser().parse(input::read("test1.txt")).unwrap();
+
+        assert_eq!(
+            almanac.maps[0].segments(),
+            [
+                Segment {
+                    source: 0..50,
+                    offset: 0
+                },
+                Segment {
+                    source: 50..98,
+                    offset: 2
+                },
+                Segment {
+                    source: 98..100,
+                    offset: -48
+                },
+                Segment {
+                    source: 100..usize::MAX,
+                    offset: 0
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compose() {
+        let almanac = parser().parse(input::read("test1.txt")).unwrap();
+        let composed = almanac.compose();
+
+        for seed in 0..120 {
+            assert_eq!(composed.get(seed), almanac.calculate_seed(seed));
+        }
+
+        assert_eq!(composed.get(usize::MAX), usize::MAX);
+        assert_eq!(Composed::identity().get(usize::MAX), usize::MAX);
+
+        assert_eq!(composed.segments.first().unwrap().source.start, 0);
+        assert_eq!(composed.segments.last().unwrap().source.end, usize::MAX);
+        assert!(composed
+            .segments
+            .windows(2)
+            .all(|w| w[0].source.end == w[1].source.start));
+    }
+
+    #[test]
+    fn test_map_ranges() {
+        let almanac = parser().parse(input::read("test1.txt")).unwrap();
+        let composed = almanac.compose();
+
+        let ranges = vec![79..93, 55..68];
+        assert_eq!(
+            composed.map_ranges(&ranges),
+            almanac
+                .maps
+                .iter()
+                .fold(ranges.clone(), |ranges, map| map.map_ranges(&ranges))
+        );
+        assert_eq!(composed.map_ranges(&ranges)[0].start, 46);
+    }
+}
@@ -0,0 +1,219 @@
+use serde::Serialize;
+
+use crate::{Almanac, Map};
+
+#[derive(Serialize)]
+struct JsonRange {
+    destination: usize,
+    source: usize,
+    length: usize,
+}
+
+#[derive(Serialize)]
+struct JsonMap<'a> {
+    source: &'a str,
+    destination: &'a str,
+    ranges: Vec<JsonRange>,
+}
+
+#[derive(Serialize)]
+struct JsonAlmanac<'a> {
+    seeds: &'a [usize],
+    maps: Vec<JsonMap<'a>>,
+}
+
+/// Writes the almanac back in the puzzle format, with the maps in their
+/// current order.
+pub fn to_text(almanac: &Almanac) -> String {
+    let seeds = almanac
+        .seeds
+        .iter()
+        .map(|s| s.to_string())
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let mut out = format!("seeds: {seeds}\n");
+    for map in &almanac.maps {
+        out.push_str(&format!("\n{}-to-{} map:\n", map.source, map.destination));
+
+        for mr in &map.ranges {
+            out.push_str(&format!(
+                "{} {} {}\n",
+                mr.destination.start,
+                mr.source.start,
+                mr.source.len()
+            ));
+        }
+    }
+
+    out
+}
+
+pub fn to_json(almanac: &Almanac) -> String {
+    let json = JsonAlmanac {
+        seeds: &almanac.seeds,
+        maps: almanac
+            .maps
+            .iter()
+            .map(|map| JsonMap {
+                source: &map.source,
+                destination: &map.destination,
+                ranges: map
+                    .ranges
+                    .iter()
+                    .map(|mr| JsonRange {
+                        destination: mr.destination.start,
+                        source: mr.source.start,
+                        length: mr.source.len(),
+                    })
+                    .collect(),
+            })
+            .collect(),
+    };
+
+    serde_json::to_string_pretty(&json).unwrap()
+}
+
+const WIDTH: f64 = 800.0;
+const MARGIN: f64 = 20.0;
+const PANEL: f64 = 130.0;
+const BAR: f64 = 10.0;
+
+/// One panel per map: source ranges on the top bar, destination ranges on
+/// the bottom bar and a band between each pair.
+fn panel(map: &Map, top: f64) -> String {
+    let max = map
+        .ranges
+        .iter()
+        .flat_map(|mr| [mr.source.end, mr.destination.end])
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let x = |id: usize| MARGIN + id as f64 * (WIDTH - 2.0 * MARGIN) / max as f64;
+
+    let (source, destination) = (top + 25.0, top + 100.0);
+    let mut out = format!(
+        "  <text x=\"{MARGIN}\" y=\"{:.1}\">{}-to-{} map</text>\n",
+        top + 15.0,
+        map.source,
+        map.destination
+    );
+
+    for y in [source, destination] {
+        out.push_str(&format!(
+            "  <rect x=\"{MARGIN}\" y=\"{y:.1}\" width=\"{:.1}\" height=\"{BAR}\" fill=\"#eee\"/>\n",
+            WIDTH - 2.0 * MARGIN
+        ));
+    }
+
+    for (i, mr) in map.ranges.iter().enumerate() {
+        let (s, d) = (&mr.source, &mr.destination);
+        let hue = i * 360 / map.ranges.len();
+
+        out.push_str(&format!(
+            "  <g fill=\"hsl({hue}, 60%, 50%)\">\n    <title>{}..{} to {}..{}</title>\n",
+            s.start, s.end, d.start, d.end
+        ));
+        for (range, y) in [(s, source), (d, destination)] {
+            out.push_str(&format!(
+                "    <rect x=\"{:.1}\" y=\"{y:.1}\" width=\"{:.1}\" height=\"{BAR}\"/>\n",
+                x(range.start),
+                x(range.end) - x(range.start)
+            ));
+        }
+        out.push_str(&format!(
+            "    <polygon points=\"{:.1},{:.1} {:.1},{:.1} {:.1},{:.1} {:.1},{:.1}\" opacity=\"0.4\"/>\n  </g>\n",
+            x(s.start),
+            source + BAR,
+            x(s.end),
+            source + BAR,
+            x(d.end),
+            destination,
+            x(d.start),
+            destination
+        ));
+    }
+
+    out
+}
+
+/// Band diagram of every map, each one scaled to its own ranges.
+pub fn to_svg(almanac: &Almanac) -> String {
+    let height = PANEL * almanac.maps.len() as f64;
+    let mut out = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{WIDTH}\" height=\"{height}\" font-family=\"monospace\" font-size=\"12\">\n"
+    );
+
+    for (i, map) in almanac.maps.iter().enumerate() {
+        out.push_str(&panel(map, PANEL * i as f64));
+    }
+
+    out.push_str("</svg>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use chumsky::Parser;
+
+    use super::*;
+    use crate::parser;
+
+    #[test]
+    fn test_round_trip() {
+        for path in ["test1.txt", "input.txt"] {
+            let src = input::read(path);
+            let almanac = parser().parse(src.clone()).unwrap();
+
+            assert_eq!(to_text(&almanac), src);
+            assert_eq!(
+                parser().parse(to_text(&almanac)).unwrap().maps,
+                almanac.maps
+            );
+        }
+    }
+
+    #[test]
+    fn test_round_trip_unordered() {
+        let src = input::read("test1.txt");
+        let mut sections = src.split("\n\n").map(str::trim_end).collect::<Vec<_>>();
+        sections[1..].reverse();
+        let src = sections.join("\n\n") + "\n";
+
+        let almanac = parser().parse(src.clone()).unwrap();
+        assert_eq!(to_text(&almanac), src);
+        assert_ne!(to_text(&almanac.ordered().unwrap()), src);
+    }
+
+    #[test]
+    fn test_json() {
+        let almanac = parser().parse(input::read("test1.txt")).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&to_json(&almanac)).unwrap();
+
+        assert_eq!(json["seeds"], serde_json::json!([79, 14, 55, 13]));
+        assert_eq!(json["maps"].as_array().unwrap().len(), 7);
+        assert_eq!(
+            json["maps"][0],
+            serde_json::json!({
+                "source": "seed",
+                "destination": "soil",
+                "ranges": [
+                    { "destination": 50, "source": 98, "length": 2 },
+                    { "destination": 52, "source": 50, "length": 48 },
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn test_svg() {
+        let almanac = parser().parse(input::read("test1.txt")).unwrap();
+        let svg = to_svg(&almanac);
+
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<polygon").count(), 18);
+        assert!(svg.contains("<title>98..100 to 50..52</title>"));
+        assert!(svg.contains("humidity-to-location map"));
+    }
+}
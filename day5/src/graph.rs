@@ -0,0 +1,263 @@
+use std::{collections::HashMap, fmt, ops::Range};
+
+use crate::{Almanac, Map};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChainError {
+    /// Every category is the destination of a map, so there is nowhere to
+    /// start from.
+    NoStart,
+    /// Several maps convert from `category`.
+    DuplicateLink { category: String },
+    /// The chain stops at `category` but some maps are never reached.
+    MissingLink { category: String },
+    /// Following the maps comes back to `category`.
+    Cycle { category: String },
+}
+
+impl fmt::Display for ChainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChainError::NoStart => write!(f, "every category is converted from another one"),
+            ChainError::DuplicateLink { category } => {
+                write!(f, "more than one map converts from {category}")
+            }
+            ChainError::MissingLink { category } => {
+                write!(
+                    f,
+                    "no map converts from {category}, some maps are unreachable"
+                )
+            }
+            ChainError::Cycle { category } => write!(f, "{category} is converted back to itself"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryError {
+    UnknownCategory(String),
+    NoPath { from: String, to: String },
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryError::UnknownCategory(category) => write!(f, "unknown category {category}"),
+            QueryError::NoPath { from, to } => write!(f, "no maps lead from {from} to {to}"),
+        }
+    }
+}
+
+impl Almanac {
+    /// Maps indexed by the category they convert from. The first one wins
+    /// when several maps share a source.
+    fn links(&self) -> HashMap<&str, &Map> {
+        let mut links = HashMap::new();
+
+        for map in &self.maps {
+            links.entry(map.source.as_str()).or_insert(map);
+        }
+
+        links
+    }
+
+    /// The maps in the order they have to be applied, from the only category
+    /// no map converts to.
+    pub fn chain(&self) -> Result<Vec<&Map>, Vec<ChainError>> {
+        let mut errors = vec![];
+
+        for (i, map) in self.maps.iter().enumerate() {
+            let first = self.maps[..i].iter().all(|m| m.source != map.source);
+            let count = self.maps.iter().filter(|m| m.source == map.source).count();
+
+            if first && count > 1 {
+                errors.push(ChainError::DuplicateLink {
+                    category: map.source.clone(),
+                });
+            }
+        }
+
+        let Some(start) = self
+            .maps
+            .iter()
+            .find(|map| self.maps.iter().all(|m| m.destination != map.source))
+        else {
+            errors.push(ChainError::NoStart);
+            return Err(errors);
+        };
+
+        let links = self.links();
+        let mut chain = vec![];
+        let mut category = start.source.as_str();
+
+        while let Some(map) = links.get(category) {
+            if chain.iter().any(|m: &&Map| m.source == map.source) {
+                errors.push(ChainError::Cycle {
+                    category: category.to_string(),
+                });
+                break;
+            }
+
+            chain.push(*map);
+            category = &map.destination;
+        }
+
+        if errors.is_empty() && chain.len() < links.len() {
+            errors.push(ChainError::MissingLink {
+                category: category.to_string(),
+            });
+        }
+
+        if errors.is_empty() {
+            Ok(chain)
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Reorders the maps along the chain.
+    pub fn ordered(mut self) -> Result<Self, Vec<ChainError>> {
+        let chain = self.chain()?.into_iter().cloned().collect();
+        self.maps = chain;
+        Ok(self)
+    }
+
+    /// Maps converting `from` into `to`, following the category names.
+    pub fn path(&self, from: &str, to: &str) -> Result<Vec<&Map>, QueryError> {
+        for category in [from, to] {
+            if !self
+                .maps
+                .iter()
+                .any(|m| m.source == category || m.destination == category)
+            {
+                return Err(QueryError::UnknownCategory(category.to_string()));
+            }
+        }
+
+        let links = self.links();
+        let mut path = vec![];
+        let mut category = from;
+
+        while category != to {
+            match links.get(category) {
+                Some(map) if path.len() < links.len() => {
+                    path.push(*map);
+                    category = &map.destination;
+                }
+                _ => {
+                    return Err(QueryError::NoPath {
+                        from: from.to_string(),
+                        to: to.to_string(),
+                    })
+                }
+            }
+        }
+
+        Ok(path)
+    }
+
+    pub fn convert(&self, from: &str, to: &str, id: usize) -> Result<usize, QueryError> {
+        Ok(self.path(from, to)?.iter().fold(id, |id, map| map.get(id)))
+    }
+
+    pub fn convert_ranges(
+        &self,
+        from: &str,
+        to: &str,
+        ranges: &[Range<usize>],
+    ) -> Result<Vec<Range<usize>>, QueryError> {
+        Ok(self
+            .path(from, to)?
+            .iter()
+            .fold(ranges.to_vec(), |ranges, map| map.map_ranges(&ranges)))
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::single_range_in_vec_init)]
+mod tests {
+    use chumsky::Parser;
+
+    use super::*;
+    use crate::parser;
+
+    /// test1.txt with its sections in reverse order.
+    fn shuffled() -> Almanac {
+        let src = input::read("test1.txt");
+        let mut sections = src.split("\n\n").collect::<Vec<_>>();
+        sections[1..].reverse();
+
+        parser().parse(sections.join("\n\n")).unwrap()
+    }
+
+    #[test]
+    fn test_chain() {
+        let almanac = parser().parse(input::read("test1.txt")).unwrap();
+        let shuffled = shuffled();
+
+        assert_eq!(shuffled.maps[0].source, "humidity");
+        assert_eq!(
+            shuffled.chain().unwrap(),
+            almanac.maps.iter().collect::<Vec<_>>()
+        );
+
+        let ordered = shuffled.ordered().unwrap();
+        assert_eq!(
+            ordered.lowest_location(&[79..80, 14..15, 55..56, 13..14]),
+            Some(35)
+        );
+    }
+
+    #[test]
+    fn test_chain_errors() {
+        let mut almanac = parser().parse(input::read("test1.txt")).unwrap();
+        almanac.maps.remove(2);
+        assert_eq!(
+            almanac.chain(),
+            Err(vec![ChainError::MissingLink {
+                category: "fertilizer".to_string()
+            }])
+        );
+
+        let mut almanac = parser().parse(input::read("test1.txt")).unwrap();
+        almanac.maps.push(almanac.maps[1].clone());
+        assert_eq!(
+            almanac.chain(),
+            Err(vec![ChainError::DuplicateLink {
+                category: "soil".to_string()
+            }])
+        );
+
+        let mut almanac = parser().parse(input::read("test1.txt")).unwrap();
+        almanac.maps[6].destination = "seed".to_string();
+        assert_eq!(almanac.chain(), Err(vec![ChainError::NoStart]));
+    }
+
+    #[test]
+    fn test_convert() {
+        let almanac = shuffled();
+        let in_order = parser().parse(input::read("test1.txt")).unwrap();
+
+        assert_eq!(almanac.convert("seed", "water", 79), Ok(81));
+        assert_eq!(almanac.convert("seed", "location", 13), Ok(35));
+        assert_eq!(almanac.convert("light", "light", 5), Ok(5));
+        assert_eq!(
+            almanac.convert_ranges("soil", "humidity", &[81..95]),
+            Ok(in_order.maps[1..6]
+                .iter()
+                .fold(vec![81..95], |ranges, map| map.map_ranges(&ranges)))
+        );
+
+        assert_eq!(
+            almanac.convert("water", "seed", 1),
+            Err(QueryError::NoPath {
+                from: "water".to_string(),
+                to: "seed".to_string()
+            })
+        );
+        assert_eq!(
+            almanac.convert("seed", "gold", 1),
+            Err(QueryError::UnknownCategory("gold".to_string()))
+        );
+    }
+}
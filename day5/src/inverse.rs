@@ -0,0 +1,147 @@
+use std::ops::Range;
+
+use crate::{merge_ranges, Almanac, Map};
+
+/// Ids present in both `a` and `b`.
+pub fn intersect(a: &[Range<usize>], b: &[Range<usize>]) -> Vec<Range<usize>> {
+    let (a, b) = (merge_ranges(a.to_vec()), merge_ranges(b.to_vec()));
+    let mut out = vec![];
+
+    for x in &a {
+        for y in &b {
+            let start = x.start.max(y.start);
+            let end = x.end.min(y.end);
+
+            if start < end {
+                out.push(start..end);
+            }
+        }
+    }
+
+    merge_ranges(out)
+}
+
+impl Map {
+    /// Every id mapped into `ranges`. Segments are used rather than the raw
+    /// map ranges so an id left untouched by the map is only its own preimage
+    /// when no map range claims it.
+    pub fn inverse_ranges(&self, ranges: &[Range<usize>]) -> Vec<Range<usize>> {
+        let mut out = vec![];
+
+        for segment in &self.index {
+            for range in ranges {
+                // Unmap in i128 since `range.end` may be `usize::MAX`
+                let unmap = |id: usize| id as i128 - segment.offset as i128;
+
+                let start = unmap(range.start).max(segment.source.start as i128);
+                let end = unmap(range.end).min(segment.source.end as i128);
+
+                if start < end {
+                    out.push(start as usize..end as usize);
+                }
+            }
+        }
+
+        merge_ranges(out)
+    }
+}
+
+impl Almanac {
+    /// Seeds ending up in any of the `locations`.
+    pub fn seeds_for_locations(&self, locations: &[Range<usize>]) -> Vec<Range<usize>> {
+        self.maps
+            .iter()
+            .rev()
+            .fold(merge_ranges(locations.to_vec()), |ranges, map| {
+                map.inverse_ranges(&ranges)
+            })
+    }
+
+    pub fn seeds_for_location(&self, location: usize) -> Vec<Range<usize>> {
+        self.seeds_for_locations(&[Range {
+            start: location,
+            end: location + 1,
+        }])
+    }
+
+    /// Same as `lowest_location` but searching from the locations: binary
+    /// search for the smallest `n` such that a seed of `seeds` reaches `0..n`.
+    pub fn lowest_location_reverse(&self, seeds: &[Range<usize>]) -> Option<usize> {
+        let reaches = |end: usize| {
+            let found = self.seeds_for_locations(&[Range { start: 0, end }]);
+            !intersect(&found, seeds).is_empty()
+        };
+
+        if !reaches(usize::MAX) {
+            return None;
+        }
+
+        let (mut lo, mut hi) = (0, usize::MAX);
+        while lo + 1 < hi {
+            let mid = lo + (hi - lo) / 2;
+            if reaches(mid) {
+                hi = mid;
+            } else {
+                lo = mid;
+            }
+        }
+
+        Some(lo)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::single_range_in_vec_init)]
+mod tests {
+    use chumsky::Parser;
+
+    use super::*;
+    use crate::{parser, SeedSpec};
+
+    #[test]
+    fn test_intersect() {
+        assert_eq!(intersect(&[0..10, 20..30], &[5..25]), [5..10, 20..25]);
+        assert_eq!(intersect(&[0..10], &[10..20]), []);
+    }
+
+    #[test]
+    fn test_inverse_gaps() {
+        let almanac = parser().parse(input::read("test1.txt")).unwrap();
+        let seed_to_soil = &almanac.maps[0];
+
+        // Soil 50 comes from seed 98 but not from seed 50, which becomes soil 52
+        assert_eq!(seed_to_soil.inverse_ranges(&[50..51]), [98..99]);
+        assert_eq!(seed_to_soil.inverse_ranges(&[52..53]), [50..51]);
+        assert_eq!(seed_to_soil.inverse_ranges(&[10..11]), [10..11]);
+        assert_eq!(seed_to_soil.inverse_ranges(&[99..101]), [97..98, 100..101]);
+    }
+
+    #[test]
+    fn test_seeds_for_location() {
+        let almanac = parser().parse(input::read("test1.txt")).unwrap();
+
+        for seed in 0..120 {
+            let location = almanac.calculate_seed(seed);
+            let seeds = almanac.seeds_for_location(location);
+
+            assert!(seeds.iter().any(|r| r.contains(&seed)));
+            for seed in seeds.iter().flat_map(|r| r.clone()).filter(|s| *s < 200) {
+                assert_eq!(almanac.calculate_seed(seed), location);
+            }
+        }
+    }
+
+    #[test]
+    fn test_lowest_location_reverse() {
+        let almanac = parser().parse(input::read("test1.txt")).unwrap();
+
+        assert_eq!(
+            almanac.lowest_location_reverse(&almanac.seed_ranges(SeedSpec::Ranges).unwrap()),
+            Some(46)
+        );
+        assert_eq!(
+            almanac.lowest_location_reverse(&[79..80, 14..15, 55..56, 13..14]),
+            Some(35)
+        );
+    }
+}
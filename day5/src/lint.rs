@@ -0,0 +1,132 @@
+use std::{fmt, ops::Range};
+
+use crate::{merge_ranges, Almanac, Map};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Kind {
+    /// Ids of both source ranges are mapped by whichever is listed first.
+    SourceOverlap(Range<usize>, Range<usize>),
+    /// Several ids are mapped to the same one, the map cannot be inverted
+    /// one to one.
+    DestinationOverlap(Range<usize>, Range<usize>),
+    /// Ids between the source ranges that are kept as is.
+    Gap(Range<usize>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lint {
+    pub map: String,
+    pub kind: Kind,
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: ", self.map)?;
+
+        match &self.kind {
+            Kind::SourceOverlap(a, b) => write!(f, "source ranges {a:?} and {b:?} overlap"),
+            Kind::DestinationOverlap(a, b) => {
+                write!(f, "destination ranges {a:?} and {b:?} overlap")
+            }
+            Kind::Gap(gap) => write!(f, "{gap:?} is not covered by any range"),
+        }
+    }
+}
+
+/// Pairs of overlapping ranges, each sorted by start.
+fn overlaps(ranges: impl Iterator<Item = Range<usize>>) -> Vec<(Range<usize>, Range<usize>)> {
+    let mut ranges = ranges.filter(|r| !r.is_empty()).collect::<Vec<_>>();
+    ranges.sort_by_key(|r| (r.start, r.end));
+
+    let mut found = vec![];
+    for (i, a) in ranges.iter().enumerate() {
+        for b in ranges[i + 1..].iter().take_while(|b| b.start < a.end) {
+            found.push((a.clone(), b.clone()));
+        }
+    }
+
+    found
+}
+
+impl Map {
+    pub fn lint(&self) -> Vec<Lint> {
+        let name = format!("{}-to-{}", self.source, self.destination);
+        let lint = |kind| Lint {
+            map: name.clone(),
+            kind,
+        };
+
+        let mut lints = vec![];
+
+        for (a, b) in overlaps(self.ranges.iter().map(|mr| mr.source.clone())) {
+            lints.push(lint(Kind::SourceOverlap(a, b)));
+        }
+        for (a, b) in overlaps(self.ranges.iter().map(|mr| mr.destination.clone())) {
+            lints.push(lint(Kind::DestinationOverlap(a, b)));
+        }
+
+        let covered = merge_ranges(self.ranges.iter().map(|mr| mr.source.clone()).collect());
+        for w in covered.windows(2) {
+            lints.push(lint(Kind::Gap(w[0].end..w[1].start)));
+        }
+
+        lints
+    }
+}
+
+impl Almanac {
+    pub fn lint(&self) -> Vec<Lint> {
+        self.maps.iter().flat_map(|map| map.lint()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chumsky::Parser;
+
+    use super::*;
+    use crate::{parser, MapRanges};
+
+    #[test]
+    fn test_lint_input() {
+        let almanac = parser().parse(input::read("test1.txt")).unwrap();
+
+        assert_eq!(almanac.lint(), []);
+    }
+
+    #[test]
+    fn test_lint() {
+        let map = Map::new(
+            "a".to_string(),
+            "b".to_string(),
+            vec![
+                MapRanges {
+                    destination: 0..10,
+                    source: 20..30,
+                },
+                MapRanges {
+                    destination: 5..25,
+                    source: 25..45,
+                },
+                MapRanges {
+                    destination: 100..110,
+                    source: 50..60,
+                },
+            ],
+        );
+
+        let lints = map.lint();
+        assert_eq!(
+            lints.iter().map(|l| l.kind.clone()).collect::<Vec<_>>(),
+            [
+                Kind::SourceOverlap(20..30, 25..45),
+                Kind::DestinationOverlap(0..10, 5..25),
+                Kind::Gap(45..50),
+            ]
+        );
+        assert_eq!(
+            lints[2].to_string(),
+            "a-to-b: 45..50 is not covered by any range"
+        );
+    }
+}
@@ -1,57 +1,71 @@
-use std::{
-    env::args,
-    fs,
-    ops::Range,
-    rc::Rc,
-    sync::{Arc, Mutex},
-    thread,
-};
-
-use chumsky::{chain::Chain, prelude::*, Span};
-use futures::future::{join_all, try_join_all};
+use std::{env::args, fmt, ops::Range, process};
 
-#[derive(Debug, Clone)]
-pub struct Seed(usize);
+use chumsky::prelude::*;
+use rayon::prelude::*;
 
-#[derive(Debug, Clone)]
+mod compose;
+mod export;
+mod graph;
+mod inverse;
+mod lint;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct MapRanges {
     destination: Range<usize>,
     source: Range<usize>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Map {
     destination: String,
     source: String,
     ranges: Vec<MapRanges>,
+    /// `ranges` as sorted, disjoint segments for binary search.
+    index: Vec<compose::Segment>,
 }
 
 #[derive(Debug, Clone)]
 pub struct Almanac {
-    seeds: Vec<Seed>,
+    /// The numbers of the `seeds:` line, see `SeedSpec`.
+    seeds: Vec<usize>,
     maps: Vec<Map>,
 }
 
+/// How the numbers of the `seeds:` line are read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeedSpec {
+    /// Every number is a seed.
+    Values,
+    /// Numbers go by pairs of start and length.
+    Ranges,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SeedError {
+    /// `SeedSpec::Ranges` with a start left without a length.
+    OddCount(usize),
+}
+
+impl fmt::Display for SeedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SeedError::OddCount(count) => {
+                write!(f, "{count} seeds, expected start and length pairs")
+            }
+        }
+    }
+}
+
 pub fn num_parser() -> impl Parser<char, usize, Error = Simple<char>> {
     text::int(10)
         .map(|c: String| c.parse::<usize>().unwrap())
         .padded()
 }
 
-type SeedParser = dyn Parser<char, Vec<Seed>, Error = Simple<char>>;
-
-pub fn seed_parser1() -> impl Parser<char, Vec<Seed>, Error = Simple<char>> {
-    just("seeds:").ignore_then(num_parser().map(Seed).repeated())
-}
-
-pub fn seed_parser2() -> impl Parser<char, Vec<Seed>, Error = Simple<char>> {
-    just("seeds:").ignore_then(
-        num_parser()
-            .then(num_parser())
-            .map(|(from, to)| (from..from + to).map(Seed).collect::<Vec<_>>())
-            .repeated()
-            .flatten(),
-    )
+pub fn seed_parser() -> impl Parser<char, Vec<usize>, Error = Simple<char>> {
+    just("seeds:")
+        .ignore_then(num_parser().repeated())
+        .then_ignore(text::whitespace())
 }
 
 pub fn map_parser() -> impl Parser<char, Map, Error = Simple<char>> {
@@ -70,113 +84,360 @@ pub fn map_parser() -> impl Parser<char, Map, Error = Simple<char>> {
         .then(ident)
         .then_ignore(just(" map:"))
         .then(range.repeated())
-        .map(|((source, destination), ranges)| Map {
-            source,
-            destination,
-            ranges,
-        })
+        .map(|((source, destination), ranges)| Map::new(source, destination, ranges))
 }
 
-pub fn parser1() -> impl Parser<char, Almanac, Error = Simple<char>> {
-    seed_parser1()
+pub fn parser() -> impl Parser<char, Almanac, Error = Simple<char>> {
+    seed_parser()
         .then(map_parser().repeated())
         .map(|(seeds, maps)| Almanac { seeds, maps })
 }
 
-pub fn parser2() -> impl Parser<char, Almanac, Error = Simple<char>> {
-    seed_parser2()
-        .then(map_parser().repeated())
-        .map(|(seeds, maps)| Almanac { seeds, maps })
-}
+/// Sorts ranges and merges the ones that overlap or touch.
+fn merge_ranges(mut ranges: Vec<Range<usize>>) -> Vec<Range<usize>> {
+    ranges.retain(|r| !r.is_empty());
+    ranges.sort_by_key(|r| r.start);
+
+    let mut merged: Vec<Range<usize>> = vec![];
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
 
-trait VecMapRanges {
-    fn find_map_range(&self, num: &usize) -> Option<&MapRanges>;
+    merged
 }
 
-impl VecMapRanges for Vec<MapRanges> {
-    fn find_map_range(&self, num: &usize) -> Option<&MapRanges> {
-        self.iter().find(|mr| mr.source.contains(num))
+impl Map {
+    pub fn new(source: String, destination: String, ranges: Vec<MapRanges>) -> Self {
+        let mut map = Self {
+            source,
+            destination,
+            ranges,
+            index: vec![],
+        };
+        map.index = map.segments();
+        map
+    }
+
+    pub fn get(&self, id: usize) -> usize {
+        let i = self.index.partition_point(|s| s.source.end <= id);
+        self.index.get(i).map_or(id, |s| s.apply(id))
+    }
+
+    /// Maps every id of `ranges` at once, splitting them on the boundaries of
+    /// the map ranges. Ids outside of any map range are kept as is.
+    pub fn map_ranges(&self, ranges: &[Range<usize>]) -> Vec<Range<usize>> {
+        compose::map_ranges(&self.index, ranges)
     }
 }
 
 impl Almanac {
-    fn calculate_seed(&self, seed: &Seed) -> usize {
-        let mut ids = vec![seed.0];
+    /// The seeds as read with `spec`.
+    pub fn seed_ranges(&self, spec: SeedSpec) -> Result<Vec<Range<usize>>, SeedError> {
+        match spec {
+            SeedSpec::Values => Ok(self.seeds.iter().map(|&seed| seed..seed + 1).collect()),
+            SeedSpec::Ranges if self.seeds.len() % 2 == 1 => {
+                Err(SeedError::OddCount(self.seeds.len()))
+            }
+            SeedSpec::Ranges => Ok(self
+                .seeds
+                .chunks_exact(2)
+                .map(|pair| pair[0]..pair[0] + pair[1])
+                .collect()),
+        }
+    }
 
-        for map in &self.maps {
-            // println!("  map {}-to-{}", map.source, map.destination);
-            let id = ids.last().unwrap();
-            // println!("    input: {}", id);
-            let mr = map.ranges.find_map_range(id);
-            // println!("    range: {:?}", mr);
+    /// Lowest location of the seeds read with `spec`.
+    pub fn solve(&self, spec: SeedSpec) -> Result<Option<usize>, SeedError> {
+        Ok(self.lowest_location(&self.seed_ranges(spec)?))
+    }
 
-            let next_id = match mr {
-                Some(mr) => mr.destination.start() + id - mr.source.start(),
-                None => *id,
-            };
+    /// Lowest location reachable from any seed in `seeds`.
+    pub fn lowest_location(&self, seeds: &[Range<usize>]) -> Option<usize> {
+        self.maps
+            .iter()
+            .fold(merge_ranges(seeds.to_vec()), |ranges, map| {
+                map.map_ranges(&ranges)
+            })
+            .first()
+            .map(|r| r.start)
+    }
 
-            // println!("    output: {}", next_id);
+    fn calculate_seed(&self, seed: usize) -> usize {
+        self.maps.iter().fold(seed, |id, map| map.get(id))
+    }
 
-            ids.push(next_id);
-        }
+    /// Same as `solve`, evaluating every seed one by one.
+    pub fn calculate(&self, spec: SeedSpec) -> Result<Option<usize>, SeedError> {
+        Ok(self.calculate_ranges(&self.seed_ranges(spec)?))
+    }
 
-        *ids.last().unwrap()
-    }
-
-    pub async fn calculate(&self) -> usize {
-        0
-        // let mut output = vec![];
-        //
-        // for chunk in self.seeds.chunks(32) {
-        //     output.push(tokio::spawn(async move {
-        //         chunk
-        //             .iter()
-        //             .map(|c| self.calculate_seed(c))
-        //             .collect::<Vec<_>>()
-        //     }));
-        // }
-        //
-        // let res = try_join_all(output).await;
-        //
-        // *res.iter().flatten().flatten().min().unwrap()
+    /// Brute-force version of `lowest_location`, spreading the seeds over a
+    /// work-stealing thread pool. Only meant to check the interval solver.
+    pub fn calculate_ranges(&self, seeds: &[Range<usize>]) -> Option<usize> {
+        merge_ranges(seeds.to_vec())
+            .into_par_iter()
+            .flat_map(|range| range.into_par_iter())
+            .map(|seed| self.calculate_seed(seed))
+            .min()
     }
 }
 
-pub async fn calculate(almanac: Almanac) -> usize {
-    let mut ts = vec![];
+fn main() {
+    let mut args = args();
+    args.next();
+
+    let path = args.next().unwrap();
+    let src = input::read(path);
+
+    let ordered = |almanac: Almanac| {
+        almanac.ordered().unwrap_or_else(|errors| {
+            for error in errors {
+                eprintln!("error: {error}");
+            }
+            process::exit(1);
+        })
+    };
 
-    let almanac = Arc::new(almanac);
+    let parsed = parser().parse(src).unwrap();
+    let command = args.next();
 
-    for i in 0..almanac.seeds.chunks(almanac.seeds.len() / 16).count() {
-        let almanac = almanac.clone();
-        println!("Chunk {i}");
-        ts.push(tokio::spawn(async move {
-            for seed in almanac.seeds.chunks(almanac.seeds.len() / 16).nth(i) {}
-            almanac
-                .seeds
-                .chunks(16)
-                .nth(i)
-                .unwrap_or(&[])
-                .iter()
-                .map(|seed| almanac.calculate_seed(seed))
-                .collect::<Vec<_>>()
-        }));
+    // Written back as read, the other commands follow the chain of maps
+    if command.as_deref() == Some("text") {
+        print!("{}", export::to_text(&parsed));
+        return;
     }
 
-    *join_all(ts).await.iter().flatten().flatten().min().unwrap()
+    let almanac = ordered(parsed);
+
+    let solved = |result: Result<Option<usize>, SeedError>| {
+        result.unwrap_or_else(|error| {
+            eprintln!("error: {error}");
+            process::exit(1);
+        })
+    };
+
+    match command.as_deref() {
+        Some("compose") => {
+            print!("{}", almanac.compose());
+            return;
+        }
+        Some("seeds") => {
+            let location = args.next().unwrap().parse().unwrap();
+            for range in almanac.seeds_for_location(location) {
+                println!("{}..{}", range.start, range.end);
+            }
+            return;
+        }
+        Some("convert") => {
+            let (from, to) = (args.next().unwrap(), args.next().unwrap());
+            let id = args.next().unwrap().parse().unwrap();
+
+            match almanac.convert(&from, &to, id) {
+                Ok(converted) => println!("{converted}"),
+                Err(error) => {
+                    eprintln!("error: {error}");
+                    process::exit(1);
+                }
+            }
+            return;
+        }
+        Some("json") => {
+            println!("{}", export::to_json(&almanac));
+            return;
+        }
+        Some("svg") => {
+            print!("{}", export::to_svg(&almanac));
+            return;
+        }
+        Some("lint") => {
+            for lint in almanac.lint() {
+                println!("{lint}");
+            }
+            return;
+        }
+        Some("verify") => {
+            let mut ok = true;
+
+            for spec in [SeedSpec::Values, SeedSpec::Ranges] {
+                let intervals = solved(almanac.solve(spec));
+                let brute_force = solved(almanac.calculate(spec));
+
+                println!("{spec:?}: intervals {intervals:?}, brute force {brute_force:?}");
+                ok &= intervals == brute_force;
+            }
+
+            if !ok {
+                process::exit(1);
+            }
+            return;
+        }
+        _ => {}
+    }
+
+    let lowest = |spec| {
+        solved(almanac.solve(spec)).unwrap_or_else(|| {
+            eprintln!("error: no seeds to locate");
+            process::exit(1);
+        })
+    };
+
+    println!("Part1: {}", lowest(SeedSpec::Values));
+    println!("Part2: {}", lowest(SeedSpec::Ranges));
 }
 
-#[tokio::main]
-async fn main() {
-    let mut args = args();
-    args.next();
+#[cfg(test)]
+#[allow(clippy::single_range_in_vec_init)]
+mod tests {
+    use super::*;
 
-    let path = args.next().unwrap();
-    let src = fs::read_to_string(path).unwrap();
+    #[test]
+    fn test_map_ranges() {
+        let map = Map::new(
+            "seed".to_string(),
+            "soil".to_string(),
+            vec![
+                MapRanges {
+                    destination: 50..52,
+                    source: 98..100,
+                },
+                MapRanges {
+                    destination: 52..100,
+                    source: 50..98,
+                },
+            ],
+        );
+
+        assert_eq!(map.map_ranges(&[79..93]), [81..95]);
+        assert_eq!(map.map_ranges(&[40..60]), [40..50, 52..62]);
+        assert_eq!(map.map_ranges(&[97..102]), [50..52, 99..102]);
+    }
 
-    let part1 = parser1().parse(src.clone()).unwrap();
-    println!("Part1: {}", calculate(part1).await);
-    let part2 = parser2().parse(src).unwrap();
+    #[test]
+    fn test_map_ranges_overlap() {
+        // 15..20 is in both source ranges, the first one listed wins
+        let map = Map::new(
+            "a".to_string(),
+            "b".to_string(),
+            vec![
+                MapRanges {
+                    destination: 100..110,
+                    source: 10..20,
+                },
+                MapRanges {
+                    destination: 200..210,
+                    source: 15..25,
+                },
+            ],
+        );
 
-    println!("Part2: {}", calculate(part2).await);
+        assert_eq!(map.map_ranges(&[12..22]), [102..110, 205..207]);
+        for id in 12..22 {
+            assert!(map.map_ranges(&[id..id + 1])[0].contains(&map.get(id)));
+        }
+    }
+
+    #[test]
+    fn test_get() {
+        let map = Map::new(
+            "seed".to_string(),
+            "soil".to_string(),
+            vec![
+                MapRanges {
+                    destination: 0..10,
+                    source: 20..30,
+                },
+                MapRanges {
+                    destination: 100..120,
+                    source: 10..30,
+                },
+            ],
+        );
+
+        // The first range listed wins where they overlap
+        assert_eq!(map.get(25), 5);
+        assert_eq!(map.get(15), 105);
+        assert_eq!(map.get(5), 5);
+        assert_eq!(map.get(30), 30);
+        assert_eq!(map.get(usize::MAX), usize::MAX);
+    }
+
+    #[test]
+    fn test_merge_ranges() {
+        assert_eq!(
+            merge_ranges(vec![5..8, 1..3, 3..4, 7..10, 20..20]),
+            [1..4, 5..10]
+        );
+    }
+
+    #[test]
+    fn test_part1() {
+        let almanac = parser().parse(input::read("test1.txt")).unwrap();
+        assert_eq!(almanac.solve(SeedSpec::Values), Ok(Some(35)));
+        assert_eq!(almanac.calculate(SeedSpec::Values), Ok(Some(35)));
+    }
+
+    #[test]
+    fn test_part2() {
+        let almanac = parser().parse(input::read("test1.txt")).unwrap();
+        assert_eq!(almanac.solve(SeedSpec::Ranges), Ok(Some(46)));
+        assert_eq!(almanac.calculate(SeedSpec::Ranges), Ok(Some(46)));
+    }
+
+    #[test]
+    fn test_seed_ranges() {
+        let almanac = parser().parse(input::read("test1.txt")).unwrap();
+
+        assert_eq!(almanac.seeds, [79, 14, 55, 13]);
+        assert_eq!(
+            almanac.seed_ranges(SeedSpec::Values),
+            Ok(vec![79..80, 14..15, 55..56, 13..14])
+        );
+        assert_eq!(
+            almanac.seed_ranges(SeedSpec::Ranges),
+            Ok(vec![79..93, 55..68])
+        );
+    }
+
+    #[test]
+    fn test_seed_ranges_odd() {
+        let mut almanac = parser().parse(input::read("test1.txt")).unwrap();
+        almanac.seeds.push(100);
+
+        assert_eq!(almanac.seed_ranges(SeedSpec::Values).unwrap().len(), 5);
+        assert_eq!(
+            almanac.seed_ranges(SeedSpec::Ranges),
+            Err(SeedError::OddCount(5))
+        );
+        assert_eq!(almanac.solve(SeedSpec::Ranges), Err(SeedError::OddCount(5)));
+    }
+
+    #[test]
+    fn test_no_seeds() {
+        let src = input::read("test1.txt").replacen("79 14 55 13", "", 1);
+        let almanac = parser().parse(src).unwrap();
+
+        assert_eq!(almanac.maps.len(), 7);
+        assert_eq!(almanac.solve(SeedSpec::Values), Ok(None));
+        assert_eq!(almanac.solve(SeedSpec::Ranges), Ok(None));
+    }
+
+    #[test]
+    fn test_brute_force_oracle() {
+        let almanac = parser().parse(input::read("test1.txt")).unwrap();
+
+        for start in (0..110).step_by(7) {
+            for len in [1, 5, 13, 40] {
+                let seeds = [start..start + len, start * 2..start * 2 + len / 2];
+                assert_eq!(
+                    almanac.calculate_ranges(&seeds),
+                    almanac.lowest_location(&seeds)
+                );
+            }
+        }
+
+        assert_eq!(almanac.calculate_ranges(&[]), None);
+    }
 }
@@ -0,0 +1,257 @@
+use crate::Race;
+
+/// How boats move. The puzzle boats gain 1 mm/ms per millisecond held, have no
+/// speed cap and start moving as soon as the button is released.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoatModel {
+    /// Speed gained per millisecond held, in mm/ms.
+    pub acceleration: u64,
+    /// Speed the boat cannot go past, in mm/ms.
+    pub max_speed: Option<u64>,
+    /// Milliseconds lost after releasing the button before the boat moves.
+    pub penalty: u64,
+}
+
+impl Default for BoatModel {
+    fn default() -> Self {
+        Self {
+            acceleration: 1,
+            max_speed: None,
+            penalty: 0,
+        }
+    }
+}
+
+impl BoatModel {
+    pub fn speed(&self, hold_time: u64) -> u128 {
+        let speed = hold_time as u128 * self.acceleration as u128;
+        self.max_speed.map_or(speed, |max| speed.min(max as u128))
+    }
+
+    pub fn distance(&self, time: u64, hold_time: u64) -> u128 {
+        let travel_time = time.saturating_sub(hold_time).saturating_sub(self.penalty);
+        self.speed(hold_time) * travel_time as u128
+    }
+
+    /// Without a speed cap the distance is `acceleration * h * (time - h)`
+    /// with `time` shortened by the penalty, which beats the record exactly
+    /// when `h * (time - h)` beats `record / acceleration`. `None` when the
+    /// cap makes the curve piecewise.
+    fn reduced(&self, race: &Race<u64>) -> Option<Race<u64>> {
+        let fastest = race.time as u128 * self.acceleration as u128;
+        let capped = self.max_speed.is_some_and(|max| (max as u128) < fastest);
+
+        (self.acceleration > 0 && !capped).then(|| Race {
+            time: race.time.saturating_sub(self.penalty),
+            record: race.record / self.acceleration,
+        })
+    }
+
+    /// Smallest hold time with the largest distance. The distance grows until
+    /// then and only decreases after, so the first hold time after which it
+    /// stops growing is found with a binary search.
+    fn search_optimal(&self, time: u64) -> u64 {
+        let (mut lo, mut hi) = (0, time);
+
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if self.distance(time, mid + 1) > self.distance(time, mid) {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+
+        lo
+    }
+
+    /// First and last hold times beating the record, if any.
+    pub fn winning_holds(&self, race: &Race<u64>) -> Option<(u64, u64)> {
+        if let Some(reduced) = self.reduced(race) {
+            return reduced.winning_holds();
+        }
+
+        let optimal = self.search_optimal(race.time);
+        let wins = |hold_time| self.distance(race.time, hold_time) > race.record as u128;
+        if !wins(optimal) {
+            return None;
+        }
+
+        // Winning hold times are contiguous around the optimal one
+        let (mut lo, mut hi) = (0, optimal);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if wins(mid) {
+                hi = mid;
+            } else {
+                lo = mid + 1;
+            }
+        }
+        let first = lo;
+
+        let (mut lo, mut hi) = (optimal, race.time);
+        while lo < hi {
+            let mid = hi - (hi - lo) / 2;
+            if wins(mid) {
+                lo = mid;
+            } else {
+                hi = mid - 1;
+            }
+        }
+
+        Some((first, lo))
+    }
+
+    pub fn possibilities(&self, race: &Race<u64>) -> u64 {
+        self.winning_holds(race)
+            .map_or(0, |(first, last)| last - first + 1)
+    }
+
+    /// Smallest hold time going the furthest, with that distance.
+    pub fn optimal_hold(&self, race: &Race<u64>) -> (u64, u128) {
+        let hold_time = match self.reduced(race) {
+            Some(reduced) => reduced.time / 2,
+            None => self.search_optimal(race.time),
+        };
+
+        (hold_time, self.distance(race.time, hold_time))
+    }
+
+    /// Distance past the record for at most `samples` hold times spread evenly
+    /// from 0 to the race time, both included. `samples` is at least 2 so that
+    /// both ends are always there.
+    pub fn margins(&self, race: &Race<u64>, samples: u64) -> Vec<(u64, i128)> {
+        let samples = samples.max(2);
+        let step = race.time.div_ceil(samples - 1).max(1);
+        let mut holds = (0..=race.time).step_by(step as usize).collect::<Vec<_>>();
+        if holds.last() != Some(&race.time) {
+            holds.push(race.time);
+        }
+
+        holds
+            .into_iter()
+            .map(|hold_time| {
+                let distance = i128::try_from(self.distance(race.time, hold_time));
+                (
+                    hold_time,
+                    distance.unwrap_or(i128::MAX) - race.record as i128,
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn brute_force(model: &BoatModel, race: &Race<u64>) -> u64 {
+        (0..=race.time)
+            .filter(|h| model.distance(race.time, *h) > race.record as u128)
+            .count() as u64
+    }
+
+    fn models() -> Vec<BoatModel> {
+        let mut models = vec![];
+        for acceleration in [0, 1, 2, 3] {
+            for max_speed in [None, Some(0), Some(5), Some(12), Some(100)] {
+                for penalty in [0, 1, 4] {
+                    models.push(BoatModel {
+                        acceleration,
+                        max_speed,
+                        penalty,
+                    });
+                }
+            }
+        }
+        models
+    }
+
+    #[test]
+    fn test_default() {
+        let model = BoatModel::default();
+
+        for (time, record) in [(7, 9), (15, 40), (30, 200), (71530, 940200)] {
+            let race = Race { time, record };
+            assert_eq!(model.possibilities(&race), race.possibilities());
+        }
+
+        let race = Race { time: 7, record: 9 };
+        assert_eq!(model.optimal_hold(&race), (3, 12));
+        assert_eq!(model.winning_holds(&race), Some((2, 5)));
+    }
+
+    #[test]
+    fn test_against_brute_force() {
+        for model in models() {
+            for time in 0..40 {
+                for record in (0..400).step_by(7) {
+                    let race = Race { time, record };
+                    assert_eq!(
+                        model.possibilities(&race),
+                        brute_force(&model, &race),
+                        "{model:?} {race:?}"
+                    );
+
+                    let (hold_time, distance) = model.optimal_hold(&race);
+                    let best = (0..=time).map(|h| model.distance(time, h)).max();
+                    assert_eq!(Some(distance), best, "{model:?} {race:?}");
+                    assert!((0..hold_time).all(|h| model.distance(time, h) < distance));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_speed_cap() {
+        let model = BoatModel {
+            acceleration: 2,
+            max_speed: Some(10),
+            penalty: 1,
+        };
+        let race = Race {
+            time: 20,
+            record: 100,
+        };
+
+        // Past 5 ms the boat is at full speed and holding only loses time
+        assert_eq!(model.speed(7), 10);
+        assert_eq!(model.optimal_hold(&race), (5, 140));
+        assert_eq!(model.winning_holds(&race), Some((4, 8)));
+    }
+
+    #[test]
+    fn test_margins() {
+        let model = BoatModel::default();
+        let race = Race { time: 7, record: 9 };
+
+        let margins = model.margins(&race, 100);
+        assert_eq!(margins.len(), 8);
+        assert_eq!(margins[0], (0, -9));
+        assert_eq!(margins[3], (3, 3));
+
+        let race = Race {
+            time: 71530,
+            record: 940200,
+        };
+        let margins = model.margins(&race, 11);
+        assert_eq!(margins.first().unwrap().0, 0);
+        assert_eq!(margins.last().unwrap().0, 71530);
+        assert_eq!(margins.len(), 11);
+
+        for time in 0..50 {
+            let race = Race { time, record: 0 };
+            assert!(model.margins(&race, 10).len() <= 10);
+
+            for samples in [0, 1, 2] {
+                let holds = model
+                    .margins(&race, samples)
+                    .iter()
+                    .map(|(hold_time, _)| *hold_time)
+                    .collect::<Vec<_>>();
+                let ends = if time == 0 { vec![0] } else { vec![0, time] };
+                assert_eq!(holds, ends);
+            }
+        }
+    }
+}
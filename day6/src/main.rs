@@ -1,72 +1,228 @@
-use std::{env::args, fs};
+use std::{env::args, fmt, ops::Range, process, str::FromStr};
 
-use chumsky::prelude::*;
+use boat::BoatModel;
+use chumsky::{error::SimpleReason, prelude::*};
+use num_bigint::BigUint;
+use num_integer::{Integer, Roots};
+use num_traits::{CheckedMul, CheckedSub};
+
+mod boat;
+mod plot;
+
+/// Integer types races can be computed with.
+trait Int:
+    Integer + Roots + CheckedMul + CheckedSub + Clone + fmt::Debug + fmt::Display + FromStr
+{
+}
+
+impl<T> Int for T where
+    T: Integer + Roots + CheckedMul + CheckedSub + Clone + fmt::Debug + fmt::Display + FromStr
+{
+}
+
+fn two<T: Int>() -> T {
+    T::one() + T::one()
+}
 
 #[derive(Debug, Clone)]
-struct Race {
-    time: usize,
-    record: usize,
+struct Race<T> {
+    time: T,
+    record: T,
 }
 
-fn parser1() -> impl Parser<char, Vec<Race>, Error = Simple<char>> {
-    let num = text::int(10)
-        .map(|s: String| s.parse::<usize>().unwrap())
-        .padded();
+/// Part two reads each line as a single number, ignoring the bad kerning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Part {
+    One,
+    Two,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Error {
+    Parse(Vec<Simple<char>>),
+    /// The product of the possibilities does not fit in the integer type.
+    Overflow,
+}
+
+/// Reports values too large for `T` as a parse error rather than failing the
+/// parse, so the error points at the number instead of what comes after it.
+fn parse_int<T: Int>(digits: String, span: Range<usize>, emit: &mut dyn FnMut(Simple<char>)) -> T {
+    digits.parse().unwrap_or_else(|_| {
+        emit(Simple::custom(span, format!("{digits} does not fit")));
+        T::zero()
+    })
+}
+
+fn parser1<T: Int>() -> impl Parser<char, Vec<Race<T>>, Error = Simple<char>> {
+    let num = text::int(10).validate(parse_int).padded();
 
     let times = just("Time:").ignore_then(num.repeated());
     let distances = just("Distance:").ignore_then(num.repeated());
 
     times.then(distances).map(|(times, distances)| {
         times
-            .iter()
+            .into_iter()
             .zip(distances)
-            .map(|(time, distance)| Race {
-                time: *time,
-                record: distance,
-            })
+            .map(|(time, record)| Race { time, record })
             .collect()
     })
 }
 
-fn parser2() -> impl Parser<char, Vec<Race>, Error = Simple<char>> {
+fn parser2<T: Int>() -> impl Parser<char, Vec<Race<T>>, Error = Simple<char>> {
     let num = text::int(10)
         .padded()
         .repeated()
-        .map(|n| n.join("").parse::<usize>().unwrap());
+        .map(|n| n.join(""))
+        .validate(parse_int);
 
     let times = just("Time:").ignore_then(num);
     let distances = just("Distance:").ignore_then(num);
 
     times
         .then(distances)
-        .map(|(time, record)| Race { time, record })
-        .repeated()
+        .then_ignore(end())
+        .map(|(time, record)| vec![Race { time, record }])
 }
 
-impl Race {
-    pub fn possibilities(&self) -> usize {
-        println!("Race {self:?}");
+impl<T: Int> Race<T> {
+    /// `hold_time` is at most `time`. A distance too large for `T` is larger
+    /// than any record.
+    fn wins(&self, hold_time: &T) -> bool {
+        let travel_time = self.time.clone() - hold_time.clone();
+        travel_time
+            .checked_mul(hold_time)
+            .is_none_or(|distance| distance > self.record)
+    }
 
-        (0..self.time)
-            .filter(|hold_time| {
-                let travel_time = self.time - hold_time;
-                let distance = travel_time * hold_time;
+    /// The distance `h * (time - h)` beats `record` strictly between the roots
+    /// of `h^2 - time * h + record`, so the first winning hold time is close to
+    /// the lower root, computed with an integer square root of the
+    /// discriminant. `None` when the discriminant is negative or overflows.
+    fn first_guess(&self) -> Option<T> {
+        let four = two::<T>() * two();
+        let discriminant = self
+            .time
+            .checked_mul(&self.time)?
+            .checked_sub(&four.checked_mul(&self.record)?)?;
 
-                distance > self.record
-            })
-            .count()
+        Some((self.time.clone() - discriminant.sqrt()) / two())
+    }
+
+    /// Binary search for the first winning hold time up to `half`, or
+    /// `half + 1` if there is none.
+    fn first_by_search(&self, half: &T) -> T {
+        let (mut lo, mut hi) = (T::zero(), half.clone() + T::one());
+
+        while lo < hi {
+            let mid = lo.clone() + (hi.clone() - lo.clone()) / two();
+            if self.wins(&mid) {
+                hi = mid;
+            } else {
+                lo = mid + T::one();
+            }
+        }
+
+        lo
+    }
+
+    /// First and last hold times beating the record, if any. The guess from
+    /// the closed form is nudged by one where rounding or a root landing
+    /// exactly on the record puts it off.
+    pub fn winning_holds(&self) -> Option<(T, T)> {
+        let half = self.time.clone() / two();
+        let mut first = self
+            .first_guess()
+            .unwrap_or_else(|| self.first_by_search(&half));
+
+        while first > T::zero() && self.wins(&(first.clone() - T::one())) {
+            first = first - T::one();
+        }
+        while first <= half && !self.wins(&first) {
+            first = first + T::one();
+        }
+
+        // The distance is symmetric around `time / 2`
+        (first <= half).then(|| (first.clone(), self.time.clone() - first))
+    }
+
+    pub fn possibilities(&self) -> T {
+        self.winning_holds()
+            .map_or(T::zero(), |(first, last)| last - first + T::one())
     }
 }
 
-fn calculate_file(path: &str) -> usize {
-    let src = fs::read_to_string(path).unwrap();
-    let races = parser2().parse(src).unwrap();
+fn races<T: Int>(src: String, part: Part) -> Result<Vec<Race<T>>, Error> {
+    match part {
+        Part::One => parser1().parse(src),
+        Part::Two => parser2().parse(src),
+    }
+    .map_err(Error::Parse)
+}
 
-    races
+fn calculate_file<T: Int>(path: &str, part: Part) -> Result<T, Error> {
+    races::<T>(input::read(path), part)?
         .iter()
-        .map(|r| r.possibilities())
-        .reduce(|a, b| a * b)
-        .unwrap()
+        .try_fold(T::one(), |total, race| {
+            total.checked_mul(&race.possibilities())
+        })
+        .ok_or(Error::Overflow)
+}
+
+fn print<T: Int>(path: &str) {
+    let mut failed = false;
+
+    for (part, name) in [(Part::One, "Part 1"), (Part::Two, "Part 2")] {
+        match calculate_file::<T>(path, part) {
+            Ok(res) => println!("{name}: {res}"),
+            Err(error) => {
+                report(error);
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        process::exit(1);
+    }
+}
+
+fn report(error: Error) {
+    match error {
+        Error::Parse(errors) => {
+            for error in errors {
+                match error.reason() {
+                    SimpleReason::Custom(reason) => eprintln!("error: {reason}"),
+                    _ => eprintln!("error: {error}"),
+                }
+            }
+        }
+        Error::Overflow => eprintln!("error: the result overflows, try u128 or big"),
+    }
+}
+
+fn boat(path: &str, model: BoatModel) {
+    for (part, name) in [(Part::One, "Part 1"), (Part::Two, "Part 2")] {
+        let races = races::<u64>(input::read(path), part).unwrap_or_else(|error| {
+            report(error);
+            process::exit(1);
+        });
+
+        println!("{name}:");
+        for race in &races {
+            let (hold_time, distance) = model.optimal_hold(race);
+            println!(
+                "  {race:?}: {} ways to win, best is holding {hold_time} ms for {distance} mm",
+                model.possibilities(race)
+            );
+
+            let margins = model
+                .margins(race, 10)
+                .iter()
+                .map(|(hold_time, margin)| format!("{hold_time}:{margin:+}"))
+                .collect::<Vec<_>>();
+            println!("    margins {}", margins.join(" "));
+        }
+    }
 }
 
 fn main() {
@@ -74,8 +230,39 @@ fn main() {
     args.next();
     let path = args.next().unwrap();
 
-    let res = calculate_file(&path);
-    println!("Result: {}", res);
+    match args.next().as_deref().unwrap_or("u64") {
+        "plot" => {
+            let mut arg = |default| args.next().map_or(default, |a| a.parse().unwrap());
+            let (width, height) = (arg(72), arg(16));
+
+            for part in [Part::One, Part::Two] {
+                let races = races::<u64>(input::read(&path), part).unwrap_or_else(|error| {
+                    report(error);
+                    process::exit(1);
+                });
+
+                for race in races {
+                    println!("{race:?}");
+                    println!("{}", plot::plot(&race, width, height));
+                }
+            }
+        }
+        "boat" => {
+            let default = BoatModel::default();
+            let mut arg = || args.next();
+
+            let model = BoatModel {
+                acceleration: arg().map_or(default.acceleration, |a| a.parse().unwrap()),
+                max_speed: arg().filter(|a| a != "none").map(|a| a.parse().unwrap()),
+                penalty: arg().map_or(default.penalty, |a| a.parse().unwrap()),
+            };
+            boat(&path, model);
+        }
+        "u64" => print::<u64>(&path),
+        "u128" => print::<u128>(&path),
+        "big" => print::<BigUint>(&path),
+        int => panic!("Unknown integer type {int}, expected u64, u128, big or boat"),
+    }
 }
 
 #[cfg(test)]
@@ -84,6 +271,116 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(calculate_file("test1.txt"), 288);
+        assert_eq!(calculate_file::<u64>("test1.txt", Part::One), Ok(288));
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(calculate_file::<u64>("test1.txt", Part::Two), Ok(71503));
+        assert_eq!(
+            calculate_file::<BigUint>("test1.txt", Part::Two),
+            Ok(71503u32.into())
+        );
+    }
+
+    fn brute_force(race: &Race<u64>) -> u64 {
+        (0..race.time).filter(|h| race.wins(h)).count() as u64
+    }
+
+    #[test]
+    fn test_possibilities() {
+        let races = parser1::<u64>().parse(input::read("test1.txt")).unwrap();
+        let possibilities = races.iter().map(|r| r.possibilities()).collect::<Vec<_>>();
+        assert_eq!(possibilities, [4, 8, 9]);
+
+        let race = Race::<u64> {
+            time: 71530,
+            record: 940200,
+        };
+        assert_eq!(race.possibilities(), 71503);
+    }
+
+    #[test]
+    fn test_ties() {
+        // Holding 2 or 4 ms exactly matches the record
+        let race = Race::<u64> { time: 6, record: 8 };
+        assert_eq!(race.winning_holds(), Some((3, 3)));
+
+        let race = Race::<u64> { time: 6, record: 9 };
+        assert_eq!(race.winning_holds(), None);
+        assert_eq!(Race::<u64> { time: 0, record: 0 }.possibilities(), 0);
+    }
+
+    #[test]
+    fn test_against_brute_force() {
+        for time in 0..120 {
+            for record in 0..time * time / 4 + 2 {
+                let race = Race { time, record };
+                assert_eq!(race.possibilities(), brute_force(&race), "{race:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_against_brute_force_random() {
+        let mut state = 0x2545f4914f6cdd1du64;
+        let mut next = |max: u64| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state % max
+        };
+
+        for _ in 0..200 {
+            let time = next(100_000);
+            let record = next(time * time / 4 + 10);
+
+            let race = Race { time, record };
+            if let Some((first, last)) = race.winning_holds() {
+                assert!(race.wins(&first) && race.wins(&last));
+                assert!(first == 0 || !race.wins(&(first - 1)));
+                assert!(!race.wins(&(last + 1)));
+            }
+            assert_eq!(race.possibilities(), brute_force(&race), "{race:?}");
+        }
+    }
+
+    #[test]
+    fn test_wide_integers() {
+        // `time * time` overflows a u64, the bounds come from the search
+        let time = u64::MAX - 10;
+        let record = u64::MAX / 3;
+        let narrow = Race { time, record };
+        let wide = Race::<u128> {
+            time: time.into(),
+            record: record.into(),
+        };
+        let big = Race::<BigUint> {
+            time: time.into(),
+            record: record.into(),
+        };
+
+        assert_eq!(u128::from(narrow.possibilities()), wide.possibilities());
+        assert_eq!(BigUint::from(wide.possibilities()), big.possibilities());
+
+        let race = Race::<BigUint> {
+            time: "1".repeat(60).parse().unwrap(),
+            record: "2".repeat(100).parse().unwrap(),
+        };
+        let (first, last) = race.winning_holds().unwrap();
+        assert!(race.wins(&first) && !race.wins(&(first.clone() - 1u32)));
+        assert_eq!(last, race.time.clone() - first);
+    }
+
+    #[test]
+    fn test_parse_overflow() {
+        let src = "Time: 99999999999 99999999999\nDistance: 1 1";
+
+        assert!(parser2::<u64>().parse(src).is_err());
+        assert_eq!(
+            parser2::<u128>().parse(src).unwrap()[0].time,
+            9999999999999999999999
+        );
+        assert!(parser1::<u64>().parse(src).is_ok());
     }
 }
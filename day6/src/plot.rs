@@ -0,0 +1,147 @@
+use crate::{boat::BoatModel, Race};
+
+/// Plots distance against hold time, with the record as a line of `-`, the
+/// margin of the winning hold times shaded with `:` and their columns marked
+/// with `=` on the axis. Races longer than `width` are sampled, one hold time
+/// per column, while the bounds printed under the plot are exact.
+pub fn plot(race: &Race<u64>, width: usize, height: usize) -> String {
+    let columns = race.time.saturating_add(1).min(width.max(2) as u64) as usize;
+    let hold_times = (0..columns)
+        .map(|c| (c as u128 * race.time as u128 / (columns as u128 - 1).max(1)) as u64)
+        .collect::<Vec<_>>();
+
+    let distance = |hold_time| BoatModel::default().distance(race.time, hold_time);
+    let half = race.time / 2;
+    let top = distance(half).max(race.record as u128).max(1);
+    let rows = height.max(2) as u128 - 1;
+    // Nearest row, so that `top` is on the top row and 0 on the bottom one.
+    // Distances past 64 bits lose their low bits so the rounding fits.
+    let shift = 64u32.saturating_sub(top.leading_zeros());
+    let row = |value: u128| {
+        let (value, top) = (value >> shift, top >> shift);
+        ((value * rows * 2 + top) / (top * 2)) as usize
+    };
+
+    let bounds = race.winning_holds();
+    let wins = |h: u64| bounds.is_some_and(|(first, last)| (first..=last).contains(&h));
+    let record = row(race.record as u128);
+
+    let label_width = top.to_string().len();
+    let mut out = String::new();
+
+    for r in (0..=rows as usize).rev() {
+        let label = match r {
+            r if r == record => race.record.to_string(),
+            r if r == rows as usize => top.to_string(),
+            _ => String::new(),
+        };
+        out.push_str(&format!("{label:>label_width$} |"));
+
+        for &h in &hold_times {
+            let curve = row(distance(h));
+            let c = if r == curve {
+                '*'
+            } else if r == record {
+                '-'
+            } else if wins(h) && record < r && r < curve {
+                ':'
+            } else {
+                ' '
+            };
+            out.push(c);
+        }
+
+        out.push('\n');
+    }
+
+    let time = race.time.to_string();
+    let axis = hold_times
+        .iter()
+        .map(|&h| if wins(h) { '=' } else { '-' })
+        .collect::<String>();
+    out.push_str(&format!(
+        "{:>label_width$} +{axis}\n{:>label_width$}  0{time:>pad$}\n",
+        "",
+        "",
+        pad = columns.saturating_sub(1),
+    ));
+
+    match bounds {
+        Some((first, last)) => out.push_str(&format!(
+            "Wins holding {first} to {last} ms, {} ways\n",
+            race.possibilities()
+        )),
+        None => out.push_str("No hold time beats the record\n"),
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plot() {
+        let race = Race { time: 7, record: 9 };
+        let plot = plot(&race, 80, 6);
+
+        assert_eq!(
+            plot,
+            [
+                "12 |   **   ",
+                " 9 |--*--*--",
+                "   | *    * ",
+                "   |        ",
+                "   |        ",
+                "   |*      *",
+                "   +--====--",
+                "    0      7",
+                "Wins holding 2 to 5 ms, 4 ways",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_plot_sampled() {
+        let race = Race {
+            time: 71530,
+            record: 940200,
+        };
+        let plot = plot(&race, 60, 10);
+        let lines = plot.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 13);
+        assert!(lines[..10]
+            .iter()
+            .all(|l| l.split('|').nth(1).unwrap().len() == 60));
+        assert_eq!(lines[12], "Wins holding 14 to 71516 ms, 71503 ways");
+    }
+
+    #[test]
+    fn test_plot_no_wins() {
+        let race = Race { time: 4, record: 4 };
+        assert!(plot(&race, 80, 4).ends_with("No hold time beats the record\n"));
+        assert!(!plot(&race, 80, 4).contains(':'));
+
+        let race = Race { time: 0, record: 0 };
+        assert!(plot(&race, 80, 1).ends_with("No hold time beats the record\n"));
+    }
+
+    #[test]
+    fn test_plot_longest_race() {
+        let race = Race {
+            time: u64::MAX,
+            record: u64::MAX,
+        };
+        let plot = plot(&race, 40, 8);
+        let lines = plot.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 11);
+        assert!(lines[..8]
+            .iter()
+            .all(|l| l.split('|').nth(1).unwrap().len() == 40));
+    }
+}
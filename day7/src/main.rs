@@ -1,4 +1,4 @@
-use std::{cmp::Ordering, env::args, fs};
+use std::{cmp::Ordering, env::args};
 
 use regex::Regex;
 
@@ -161,7 +161,7 @@ fn main() {
     let mut args = args();
     args.next();
     let path = args.next().unwrap();
-    let src = fs::read_to_string(path).unwrap();
+    let src = input::read(path);
 
     let mut games = parse(&src);
 
@@ -1,4 +1,4 @@
-use std::{collections::HashMap, env::args, fs, usize};
+use std::{collections::HashMap, env::args, usize};
 
 use regex::Regex;
 
@@ -102,7 +102,7 @@ fn main() {
     let mut args = args();
     args.next();
     let path = args.next().unwrap();
-    let src = fs::read_to_string(path).unwrap();
+    let src = input::read(path);
     let (instructions, map) = parse(&src);
     let result = navigate(instructions, map);
     println!("Result: {result}");
@@ -1,4 +1,4 @@
-use std::{env::args, fs};
+use std::env::args;
 
 fn sequence(history: Vec<i32>) -> Vec<Vec<i32>> {
     let mut arr: Vec<Vec<i32>> = vec![history];
@@ -48,7 +48,7 @@ fn extrapolate_left(sequence: Vec<Vec<i32>>) -> i32 {
 fn parse(src: &str) -> Vec<Vec<i32>> {
     src.lines()
         .map(|line| {
-            line.split(' ')
+            line.split_whitespace()
                 .map(|num| num.parse::<i32>().unwrap())
                 .collect::<Vec<_>>()
         })
@@ -67,7 +67,7 @@ fn main() {
     let mut args = args();
     args.next();
     let path = args.next().unwrap();
-    let src = fs::read_to_string(path).unwrap();
+    let src = input::read(path);
     let data = parse(&src);
 
     let part1 = sequensize_right(data.clone());
@@ -0,0 +1,200 @@
+use std::{env, fmt, fs, path::Path};
+
+/// Something that was rewritten while normalizing an input. Lines start at 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    Bom,
+    CrLf { line: usize },
+    TrailingWhitespace { line: usize },
+    MissingNewline,
+    TrailingBlankLines { count: usize },
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::Bom => write!(f, "removed byte order mark"),
+            Change::CrLf { line } => write!(f, "line {line}: replaced CRLF with LF"),
+            Change::TrailingWhitespace { line } => {
+                write!(f, "line {line}: removed trailing whitespace")
+            }
+            Change::MissingNewline => write!(f, "added missing final newline"),
+            Change::TrailingBlankLines { count } => {
+                write!(f, "removed {count} trailing blank line(s)")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report(pub Vec<Change>);
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.0 {
+            writeln!(f, "  {change}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Silently fix the input.
+    Lenient,
+    /// Refuse any input that would need fixing.
+    Strict,
+}
+
+impl Mode {
+    /// Strict when `AOC_STRICT` is set, lenient otherwise.
+    pub fn from_env() -> Self {
+        match env::var_os("AOC_STRICT") {
+            Some(_) => Mode::Strict,
+            None => Mode::Lenient,
+        }
+    }
+}
+
+/// Strips a BOM, converts CRLF to LF, trims trailing whitespace on every line
+/// and makes the input end with exactly one newline.
+pub fn normalize(src: &str) -> (String, Report) {
+    let mut changes = vec![];
+
+    let src = match src.strip_prefix('\u{feff}') {
+        Some(src) => {
+            changes.push(Change::Bom);
+            src
+        }
+        None => src,
+    };
+
+    if src.is_empty() {
+        return (String::new(), Report(changes));
+    }
+
+    let mut lines = vec![];
+    for (i, line) in src.split('\n').enumerate() {
+        let line = match line.strip_suffix('\r') {
+            Some(line) => {
+                changes.push(Change::CrLf { line: i + 1 });
+                line
+            }
+            None => line,
+        };
+
+        let trimmed = line.trim_end();
+        if trimmed.len() != line.len() {
+            changes.push(Change::TrailingWhitespace { line: i + 1 });
+        }
+
+        lines.push(trimmed);
+    }
+
+    let mut blank = 0;
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+        blank += 1;
+    }
+
+    match blank {
+        0 => changes.push(Change::MissingNewline),
+        1 => (),
+        n => changes.push(Change::TrailingBlankLines { count: n - 1 }),
+    }
+
+    let mut out = lines.join("\n");
+    out.push('\n');
+
+    (out, Report(changes))
+}
+
+pub fn normalize_with(src: &str, mode: Mode) -> Result<String, Report> {
+    let (out, report) = normalize(src);
+
+    match mode {
+        Mode::Strict if !report.0.is_empty() => Err(report),
+        _ => Ok(out),
+    }
+}
+
+/// Reads and normalizes a puzzle input, see [`Mode::from_env`].
+pub fn read(path: impl AsRef<Path>) -> String {
+    let path = path.as_ref();
+    let src = fs::read_to_string(path).unwrap();
+
+    normalize_with(&src, Mode::from_env())
+        .unwrap_or_else(|report| panic!("{} is not normalized:\n{report}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalized() {
+        let (out, report) = normalize("a b\n\nc\n");
+
+        assert_eq!(out, "a b\n\nc\n");
+        assert_eq!(report, Report(vec![]));
+    }
+
+    #[test]
+    fn test_crlf_and_bom() {
+        let (out, report) = normalize("\u{feff}a\r\nb\r\n");
+
+        assert_eq!(out, "a\nb\n");
+        assert_eq!(
+            report,
+            Report(vec![
+                Change::Bom,
+                Change::CrLf { line: 1 },
+                Change::CrLf { line: 2 }
+            ])
+        );
+    }
+
+    #[test]
+    fn test_trailing_whitespace() {
+        let (out, report) = normalize("a  \nb\t\nc");
+
+        assert_eq!(out, "a\nb\nc\n");
+        assert_eq!(
+            report,
+            Report(vec![
+                Change::TrailingWhitespace { line: 1 },
+                Change::TrailingWhitespace { line: 2 },
+                Change::MissingNewline
+            ])
+        );
+    }
+
+    #[test]
+    fn test_trailing_blank_lines() {
+        let (out, report) = normalize("a\n\n \n");
+
+        assert_eq!(out, "a\n");
+        assert_eq!(
+            report,
+            Report(vec![
+                Change::TrailingWhitespace { line: 3 },
+                Change::TrailingBlankLines { count: 2 }
+            ])
+        );
+    }
+
+    #[test]
+    fn test_strict() {
+        assert_eq!(normalize_with("a\n", Mode::Strict), Ok("a\n".to_string()));
+        assert_eq!(
+            normalize_with("a\r\n", Mode::Strict),
+            Err(Report(vec![Change::CrLf { line: 1 }]))
+        );
+        assert_eq!(
+            normalize_with("a\r\n", Mode::Lenient),
+            Ok("a\n".to_string())
+        );
+        assert_eq!(normalize_with("", Mode::Strict), Ok(String::new()));
+    }
+}